@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, close_account, CloseAccount}, associated_token::AssociatedToken};
+use crate::{state::{Escrow, EXPIRE_TIP}, error::EscrowError, transfer::{transfer_checked, harvest_withheld}};
+
+// same as the refund, except anyone can sign once the escrow has expired
+#[derive(Accounts)]
+pub struct Expire <'info>{
+    #[account(mut)]
+    pub cranker: Signer<'info>, // anyone, they get EXPIRE_TIP for their trouble
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(mut)] // written to when harvesting withheld transfer fees
+    pub mint_a: Option<InterfaceAccount<'info, Mint>>, // None when the maker deposited SOL
+
+    #[account(
+        init_if_needed, // the maker might have closed their ata since, the cranker pays to recreate it
+        payer = cranker,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_a
+    )]
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump,
+        token::mint = mint_a,
+        token::authority = escrow,
+        token::token_program = token_program_a,
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow".as_ref(), maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        constraint = escrow.mint_a == mint_a.as_ref().map(|mint| mint.key()) @ EscrowError::WrongMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Expire<'info> {
+    pub fn refund(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(self.escrow.is_expired(Clock::get()?.unix_timestamp), EscrowError::NotExpired);
+
+        let (mint_a, maker_ata_a, vault) = match (&self.mint_a, &self.maker_ata_a, &self.vault) {
+            (Some(mint_a), Some(maker_ata_a), Some(vault)) => (mint_a, maker_ata_a, vault),
+            (None, None, None) => return Ok(()), // SOL goes back to the maker when the escrow is closed
+            _ => return err!(EscrowError::MissingTokenAccounts)
+        };
+
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"escrow",
+                self.maker.to_account_info().key.as_ref(),
+                &self.escrow.seed.to_le_bytes()[..],
+                &[self.escrow.bump]
+            ]
+        ];
+
+        let accounts = TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint_a.to_account_info(),
+            to: maker_ata_a.to_account_info(),
+            authority: self.escrow.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program_a.to_account_info(),
+            accounts,
+            &signer_seeds
+        ).with_remaining_accounts(remaining_accounts.to_vec()); // mint_a's transfer hook accounts, if it has one
+
+        transfer_checked(cpi_ctx, vault.amount, mint_a.decimals)
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let (Some(mint_a), Some(vault)) = (&self.mint_a, &self.vault) else {
+            return Ok(());
+        };
+
+        harvest_withheld(self.token_program_a.to_account_info(), mint_a.to_account_info(), vault.to_account_info())?;
+
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"escrow",
+                self.maker.to_account_info().key.as_ref(),
+                &self.escrow.seed.to_le_bytes()[..],
+                &[self.escrow.bump]
+            ]
+        ];
+
+        let close_accounts = CloseAccount {
+            account: vault.to_account_info(),
+            destination: self.maker.to_account_info(), // the maker paid for the vault
+            authority: self.escrow.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program_a.to_account_info(),
+            close_accounts,
+            &signer_seeds
+        );
+
+        close_account(cpi_ctx)
+    }
+
+    pub fn close_escrow(&mut self) -> Result<()> {
+        // the escrow is ours so we can move its lamports directly, no cpi needed
+        let escrow_info = self.escrow.to_account_info();
+        let tip = EXPIRE_TIP.min(escrow_info.lamports());
+        **escrow_info.try_borrow_mut_lamports()? -= tip;
+        **self.cranker.to_account_info().try_borrow_mut_lamports()? += tip;
+
+        // the rest of the rent goes back to the maker who paid it
+        self.escrow.close(self.maker.to_account_info())
+    }
+}
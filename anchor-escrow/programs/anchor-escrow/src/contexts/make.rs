@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{token::{Mint, TokenAccount, Token, Transfer, transfer}, associated_token::AssociatedToken};
-use crate::state::Escrow;
+use anchor_lang::system_program;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked}, associated_token::AssociatedToken};
+use crate::{state::Escrow, error::EscrowError, transfer::transfer_checked};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -8,17 +9,19 @@ pub struct Make <'info>{
     #[account(mut)]
     pub maker: Signer<'info>, // signer
 
-    pub mint_a: Account<'info, Mint>,
-    pub mint_b: Account<'info, Mint>,
+    // leave a mint out to trade native SOL on that leg, its token accounts are left out with it
+    pub mint_a: Option<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Option<InterfaceAccount<'info, Mint>>,
 
     #[account(
         // init, ... maker_ata should already exist if thety're making
         // payer=maker,
         mut,
         associated_token::mint = mint_a, // guaranteed to match mint_a
-        associated_token::authority = maker
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_a
     )]
-    pub maker_ata_a: Account<'info, TokenAccount>,
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         // init_if_needed, // although normally it shouldn't exist
@@ -29,9 +32,10 @@ pub struct Make <'info>{
         bump,
         token::mint = mint_a, // why not an associated token here? we could, instead of having the seeds but not sure of the effective difference
         token::authority = escrow, // why not an associated token here?
+        token::token_program = token_program_a,
         // we could even set the authority to vault
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>, // a SOL deposit sits in the escrow account itself, no vault
 
     #[account(
         init,
@@ -43,7 +47,7 @@ pub struct Make <'info>{
     pub escrow: Account<'info, Escrow>, // an account storing our escrow details
 
     pub associated_token_program: Program<'info, AssociatedToken>, // needed because our escrow uses spl tokens
-    pub token_program: Program<'info, Token>, // wouldn't be needed if we were just using sol
+    pub token_program_a: Interface<'info, TokenInterface>, // spl token or token-2022, whichever owns mint_a (mint_b can be on the other one)
     pub system_program: Program<'info, System> // must have system program if we're initializing any account
 }
 
@@ -52,32 +56,75 @@ impl<'info> Make<'info> {
     // ctx -> removed
     // self refers to the instance of the struct we're calling the fn against (Make struct)
 
-    pub fn save(&mut self, seed: u64, receive_amount: u64, bumps: &MakeBumps) -> Result<()> { // how does the MakeBumps work??
+    pub fn save(
+        &mut self,
+        seed: u64,
+        deposit_amount: u64,
+        receive_amount: u64,
+        expires_at: i64,
+        taker: Option<Pubkey>,
+        bumps: &MakeBumps // how does the MakeBumps work??
+    ) -> Result<()> {
+        require!(expires_at == 0 || expires_at > Clock::get()?.unix_timestamp, EscrowError::InvalidExpiry);
+        // a take has to pay something, an escrow asking for nothing could never be filled or priced
+        require!(receive_amount > 0, EscrowError::ZeroAmount);
+
         // save state
         self.escrow.set_inner(Escrow {
             seed,
-            mint_a: self.mint_a.key(),
-            mint_b: self.mint_b.key(),
+            mint_a: self.mint_a.as_ref().map(|mint| mint.key()),
+            mint_b: self.mint_b.as_ref().map(|mint| mint.key()),
+            deposit_amount,
             receive_amount,
+            expires_at,
+            taker,
             bump: bumps.escrow,
-            vault_bump: bumps.vault
+            vault_bump: bumps.vault // u8::MAX when there's no vault, it's never used then
         });
 
         Ok(())
     }
 
     // we decided to split the make into two functions
-    pub fn deposit(&mut self, deposit_amount: u64) -> Result<()> {
-        let accounts = Transfer {
-            from: self.maker_ata_a.to_account_info(),
-            to: self.vault.to_account_info(),
-            authority: self.maker.to_account_info()
-        };
-
-        // the token accounts belogn to the token program so the token program
-        // is the one we're gonna cpi into
-        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
-
-        transfer(cpi_ctx, deposit_amount)
+    // returns what actually landed in the escrow
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>], deposit_amount: u64) -> Result<u64> {
+        require!(deposit_amount > 0, EscrowError::ZeroAmount);
+
+        match (&self.mint_a, &self.maker_ata_a, &mut self.vault) {
+            (Some(mint_a), Some(maker_ata_a), Some(vault)) => {
+                let accounts = TransferChecked {
+                    from: maker_ata_a.to_account_info(),
+                    mint: mint_a.to_account_info(),
+                    to: vault.to_account_info(),
+                    authority: self.maker.to_account_info()
+                };
+
+                // the token accounts belogn to the token program so the token program
+                // is the one we're gonna cpi into
+                // remaining accounts are whatever mint_a's transfer hook needs, empty for plain mints
+                let cpi_ctx = CpiContext::new(self.token_program_a.to_account_info(), accounts)
+                    .with_remaining_accounts(remaining_accounts.to_vec());
+
+                transfer_checked(cpi_ctx, deposit_amount, mint_a.decimals)?;
+
+                // a transfer fee comes out of what the vault receives
+                vault.reload()?;
+                require!(vault.amount > 0, EscrowError::ZeroAmount);
+                Ok(vault.amount)
+            }
+            (None, None, None) => {
+                // native SOL, the lamports go straight into the escrow pda on top of its rent
+                let accounts = system_program::Transfer {
+                    from: self.maker.to_account_info(),
+                    to: self.escrow.to_account_info()
+                };
+
+                let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+                system_program::transfer(cpi_ctx, deposit_amount)?;
+                Ok(deposit_amount)
+            }
+            _ => err!(EscrowError::MissingTokenAccounts) // half a token leg, e.g. a mint without its vault
+        }
     }
 }
\ No newline at end of file
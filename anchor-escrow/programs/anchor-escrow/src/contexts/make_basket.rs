@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_interface::{TokenInterface, TransferChecked},
+    associated_token::{AssociatedToken, Create, create_idempotent, get_associated_token_address_with_program_id}
+};
+use crate::{state::{Basket, BasketLeg, ACCOUNTS_PER_LEG}, error::EscrowError, transfer::{mint_decimals, transfer_checked}};
+
+// the per mint accounts don't fit in a fixed struct, so they come in remaining_accounts:
+// for every offered leg, in order: [mint, maker token account, vault]
+// then whatever accounts the mints' transfer hooks need, if any of them has one
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeBasket <'info>{
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = Basket::INIT_SPACE,
+        seeds = [b"basket", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub basket: Account<'info, Basket>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>, // every leg of a basket has to use this one
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> MakeBasket<'info> {
+    pub fn save(
+        &mut self,
+        seed: u64,
+        offered: Vec<BasketLeg>,
+        requested: Vec<BasketLeg>,
+        bumps: &MakeBasketBumps
+    ) -> Result<()> {
+        Basket::check_legs(&offered)?;
+        Basket::check_legs(&requested)?;
+
+        self.basket.set_inner(Basket {
+            seed,
+            offered,
+            requested,
+            bump: bumps.basket
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let offered = self.basket.offered.clone();
+        require!(remaining_accounts.len() >= offered.len() * ACCOUNTS_PER_LEG, EscrowError::WrongLegAccounts);
+        let (legs, hook_accounts) = remaining_accounts.split_at(offered.len() * ACCOUNTS_PER_LEG);
+
+        for (leg, accounts) in offered.iter().zip(legs.chunks(ACCOUNTS_PER_LEG)) {
+            let [mint, maker_ata, vault] = accounts else {
+                return err!(EscrowError::WrongLegAccounts);
+            };
+
+            // the vaults are the basket's own atas, so creating one doesn't need a pda signature
+            require_keys_eq!(mint.key(), leg.mint, EscrowError::WrongLegAccounts);
+            require_keys_eq!(vault.key(), get_associated_token_address_with_program_id(&self.basket.key(), &leg.mint, self.token_program.key), EscrowError::WrongLegAccounts);
+
+            // idempotent because anyone can create an ata for any owner, a pre-created one shouldn't block the make
+            let create_accounts = Create {
+                payer: self.maker.to_account_info(),
+                associated_token: vault.to_account_info(),
+                authority: self.basket.to_account_info(),
+                mint: mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info()
+            };
+
+            create_idempotent(CpiContext::new(self.associated_token_program.to_account_info(), create_accounts))?;
+
+            // the token program checks the maker's account is for the same mint as the vault
+            let accounts = TransferChecked {
+                from: maker_ata.to_account_info(),
+                mint: mint.to_account_info(),
+                to: vault.to_account_info(),
+                authority: self.maker.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts)
+                .with_remaining_accounts(hook_accounts.to_vec());
+
+            transfer_checked(cpi_ctx, leg.amount, mint_decimals(mint)?)?;
+        }
+
+        Ok(())
+    }
+}
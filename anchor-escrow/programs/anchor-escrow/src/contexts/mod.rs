@@ -6,3 +6,15 @@ pub use take::*;
 
 pub mod refund;
 pub use refund::*;
+
+pub mod expire;
+pub use expire::*;
+
+pub mod make_basket;
+pub use make_basket::*;
+
+pub mod take_basket;
+pub use take_basket::*;
+
+pub mod refund_basket;
+pub use refund_basket::*;
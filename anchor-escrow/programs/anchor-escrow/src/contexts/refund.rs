@@ -1,28 +1,32 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{token::{Mint, TokenAccount, Token, Transfer, transfer, close_account, CloseAccount}, associated_token::AssociatedToken};
-use crate::state::Escrow;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, close_account, CloseAccount}, associated_token::AssociatedToken};
+use crate::{state::Escrow, error::EscrowError, transfer::{transfer_checked, harvest_withheld}};
 
 #[derive(Accounts)]
 pub struct Refund <'info>{
     #[account(mut)]
     maker: Signer<'info>, // signer
 
-    mint_a: Account<'info, Mint>,
+    #[account(mut)] // written to when harvesting withheld transfer fees
+    mint_a: Option<InterfaceAccount<'info, Mint>>, // None when the maker deposited SOL
 
     #[account(
         mut,
         associated_token::mint = mint_a,
-        associated_token::authority = maker
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_a
     )]
-    maker_ata_a: Account<'info, TokenAccount>,
+    maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
+        mut, // tokens leave it and then it's closed
         seeds = [b"vault", escrow.key().as_ref()],
         bump = escrow.vault_bump,
         token::mint = mint_a,
-        token::authority = escrow
+        token::authority = escrow,
+        token::token_program = token_program_a,
     )]
-    vault: Account<'info, TokenAccount>,
+    vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -30,23 +34,33 @@ pub struct Refund <'info>{
         // we still need to provide the seed constraints even though the account is initialized
         seeds = [b"escrow".as_ref(), maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()], // now we didn't pass seed by instruction but it's saved in the Escrow struct
         bump = escrow.bump,
+        // without this a token escrow could be closed with no vault passed, stranding the tokens in it
+        constraint = escrow.mint_a == mint_a.as_ref().map(|mint| mint.key()) @ EscrowError::WrongMint
     )]
     escrow: Account<'info, Escrow>,
 
     associated_token_program: Program<'info, AssociatedToken>, // needed because our escrow uses spl tokens
-    token_program: Program<'info, Token>, // wouldn't be needed if we were just using sol
+    token_program_a: Interface<'info, TokenInterface>, // wouldn't be needed if we were just using sol
     system_program: Program<'info, System> // must have system program if we're initializing any account
 }
 
 impl<'info> Refund<'info> {
-    pub fn refund(&mut self) -> Result<()> {
+    pub fn refund(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         // we don't need seed because it's saved
         // we don't need deposit or receive amounts because we can find what amount is in the vault
 
+        let (mint_a, maker_ata_a, vault) = match (&self.mint_a, &self.maker_ata_a, &self.vault) {
+            (Some(mint_a), Some(maker_ata_a), Some(vault)) => (mint_a, maker_ata_a, vault),
+            // a SOL deposit lives in the escrow account, close = maker sends it back with the rent
+            (None, None, None) => return Ok(()),
+            _ => return err!(EscrowError::MissingTokenAccounts)
+        };
+
         // refund: vault -> maker
-        let accounts = Transfer {
-            from: self.vault.to_account_info(),
-            to: self.maker_ata_a.to_account_info(),
+        let accounts = TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint_a.to_account_info(),
+            to: maker_ata_a.to_account_info(),
             authority: self.escrow.to_account_info()
         };
 
@@ -63,15 +77,21 @@ impl<'info> Refund<'info> {
 
         // ! CpiContext::new_with_signer - use when signing on behalf of a PDA
         let cpi_ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
+            self.token_program_a.to_account_info(),
             accounts,
             &signer_seeds
-        );
+        ).with_remaining_accounts(remaining_accounts.to_vec()); // mint_a's transfer hook accounts, if it has one
 
-        transfer(cpi_ctx, self.vault.amount)
+        transfer_checked(cpi_ctx, vault.amount, mint_a.decimals)
     }
 
     pub fn close_vault(&mut self) -> Result<()> {
+        let (Some(mint_a), Some(vault)) = (&self.mint_a, &self.vault) else {
+            return Ok(()); // nothing to close for a SOL deposit
+        };
+
+        harvest_withheld(self.token_program_a.to_account_info(), mint_a.to_account_info(), vault.to_account_info())?;
+
         // same seeds
         let signer_seeds: [&[&[u8]]; 1] = [
             &[
@@ -83,13 +103,13 @@ impl<'info> Refund<'info> {
         ];
 
         let close_accounts = CloseAccount {
-            account: self.vault.to_account_info(),
+            account: vault.to_account_info(),
             destination: self.maker.to_account_info(), // where to send the lamports from closing this account
             authority: self.escrow.to_account_info()
         };
 
         let cpi_ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
+            self.token_program_a.to_account_info(),
             close_accounts,
             &signer_seeds // same signer seeds because same signer
         );
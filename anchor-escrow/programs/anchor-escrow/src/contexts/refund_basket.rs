@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::accessor,
+    token_interface::{TokenInterface, TransferChecked, CloseAccount, close_account},
+    associated_token::get_associated_token_address_with_program_id
+};
+use crate::{state::{Basket, ACCOUNTS_PER_LEG}, error::EscrowError, transfer::{mint_decimals, transfer_checked, harvest_withheld}};
+
+// remaining_accounts, for every offered leg in order: [mint (writable), vault, maker token account]
+// then any transfer hook accounts the mints need
+#[derive(Accounts)]
+pub struct RefundBasket <'info>{
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"basket", maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump
+    )]
+    pub basket: Account<'info, Basket>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> RefundBasket<'info> {
+    pub fn refund(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // every vault has to be emptied, otherwise closing the basket would strand its tokens
+        require!(remaining_accounts.len() >= self.basket.offered.len() * ACCOUNTS_PER_LEG, EscrowError::WrongLegAccounts);
+        let (legs, hook_accounts) = remaining_accounts.split_at(self.basket.offered.len() * ACCOUNTS_PER_LEG);
+
+        let seed = self.basket.seed.to_le_bytes();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"basket",
+                self.maker.to_account_info().key.as_ref(),
+                &seed[..],
+                &[self.basket.bump]
+            ]
+        ];
+
+        for (leg, accounts) in self.basket.offered.iter().zip(legs.chunks(ACCOUNTS_PER_LEG)) {
+            let [mint, vault, maker_ata] = accounts else {
+                return err!(EscrowError::WrongLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), leg.mint, EscrowError::WrongLegAccounts);
+            require_keys_eq!(vault.key(), get_associated_token_address_with_program_id(&self.basket.key(), &leg.mint, self.token_program.key), EscrowError::WrongLegAccounts);
+
+            let amount = accessor::amount(vault)?;
+
+            let accounts = TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: maker_ata.to_account_info(),
+                authority: self.basket.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                accounts,
+                &signer_seeds
+            ).with_remaining_accounts(hook_accounts.to_vec());
+
+            transfer_checked(cpi_ctx, amount, mint_decimals(mint)?)?;
+
+            harvest_withheld(self.token_program.to_account_info(), mint.to_account_info(), vault.to_account_info())?;
+
+            let close_accounts = CloseAccount {
+                account: vault.to_account_info(),
+                destination: self.maker.to_account_info(), // the maker paid for the vaults
+                authority: self.basket.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                close_accounts,
+                &signer_seeds
+            );
+
+            close_account(cpi_ctx)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{token::{Mint, TokenAccount, Token, Transfer, transfer, CloseAccount, close_account}, associated_token::AssociatedToken};
-use crate::state::Escrow;
+use anchor_lang::system_program;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account}, associated_token::AssociatedToken};
+use crate::{state::Escrow, error::EscrowError, transfer::{transfer_checked, harvest_withheld, net_amount, gross_amount}};
 
 #[derive(Accounts)]
 pub struct Take <'info>{
@@ -10,42 +11,48 @@ pub struct Take <'info>{
     #[account(mut)]
     pub maker: SystemAccount<'info>,
 
-    pub mint_a: Account<'info, Mint>,
-    pub mint_b: Account<'info, Mint>,
+    // None for a native SOL leg, same as in make
+    #[account(mut)] // harvesting withheld transfer fees before closing the vault writes to the mint
+    pub mint_a: Option<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Option<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init_if_needed, // the taker might not have an account for the receiving token mint_a already!
         payer = taker,
         associated_token::mint = mint_a,
-        associated_token::authority = taker
+        associated_token::authority = taker,
+        associated_token::token_program = token_program_a
     )]
-    pub taker_ata_a: Account<'info, TokenAccount>,
+    pub taker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut, // the taker must have a mint_b ata already or else they wouldn't be taking the escrow
         associated_token::mint = mint_b,
-        associated_token::authority = taker
+        associated_token::authority = taker,
+        associated_token::token_program = token_program_b
     )]
-    pub taker_ata_b: Account<'info, TokenAccount>,
+    pub taker_ata_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed, // the maker might not have an account for the receiving token mint_b already!
         payer=taker,
         associated_token::mint = mint_b,
-        associated_token::authority = maker
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_b
     )]
-    pub maker_ata_b: Account<'info, TokenAccount>,
+    pub maker_ata_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        mut, // because we're gonna close the account
-        // setting close = maker would be ok too
-        close = taker, // we choose to give that to the taker to offset that they will likely have to pay for creating maker's ata for mint_b
+        mut, // the remaining amounts change on every fill, and the last one closes it (see close_escrow)
         seeds = [b"escrow".as_ref(), maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
-        // the has_one constraints are really important here!
+        // these constraints are really important here! (they used to be has_one, but the mints are optional now)
         // someone malicious could deposit any other token insted of mint_b and take mint_a
-        has_one = mint_a,
-        has_one = mint_b
+        // or pass no mint_b at all and pay in SOL for a token leg
+        constraint = escrow.mint_a == mint_a.as_ref().map(|mint| mint.key()) @ EscrowError::WrongMint,
+        constraint = escrow.mint_b == mint_b.as_ref().map(|mint| mint.key()) @ EscrowError::WrongMint,
+        // a private deal can only be settled by the counterparty the maker agreed with
+        constraint = escrow.taker.is_none() || escrow.taker == Some(taker.key()) @ EscrowError::TakerNotAllowed
     )]
     pub escrow: Account<'info, Escrow>,
 
@@ -55,57 +62,126 @@ pub struct Take <'info>{
         bump = escrow.vault_bump,
         token::mint = mint_a,
         token::authority = escrow,
+        token::token_program = token_program_a,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    // one per leg, so a legacy spl mint can trade against a token-2022 one
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System> // must have system program if we're initializing any account
 }
 
 impl<'info> Take<'info> {
-    // Send money from taker to maker
-    pub fn deposit(&mut self) -> Result<()> {
-        let accounts = Transfer {
-            from: self.taker_ata_b.to_account_info(),
-            to: self.maker_ata_b.to_account_info(),
-            authority: self.taker.to_account_info()
-        };
+    // what the maker actually gets out of amount_b, a token-2022 transfer fee on mint_b comes out of it
+    pub fn net_b(&self, amount_b: u64) -> Result<u64> {
+        match &self.mint_b {
+            Some(mint_b) => net_amount(&mint_b.to_account_info(), amount_b),
+            None => Ok(amount_b)
+        }
+    }
 
-        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+    // what the taker has to send for the maker to end up with everything the escrow still asks for
+    pub fn gross_receive_amount(&self) -> Result<u64> {
+        match &self.mint_b {
+            Some(mint_b) => gross_amount(&mint_b.to_account_info(), self.escrow.receive_amount),
+            None => Ok(self.escrow.receive_amount)
+        }
+    }
 
-        transfer(cpi_ctx, self.escrow.receive_amount)
+    // Send money from taker to maker
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>], amount_b: u64, net_b: u64) -> Result<()> {
+        match (&self.mint_b, &self.taker_ata_b, &self.maker_ata_b) {
+            (Some(mint_b), Some(taker_ata_b), Some(maker_ata_b)) => {
+                let accounts = TransferChecked {
+                    from: taker_ata_b.to_account_info(),
+                    mint: mint_b.to_account_info(),
+                    to: maker_ata_b.to_account_info(),
+                    authority: self.taker.to_account_info()
+                };
+
+                // the hook accounts for both mints come in together, each transfer picks out its own
+                let cpi_ctx = CpiContext::new(self.token_program_b.to_account_info(), accounts)
+                    .with_remaining_accounts(remaining_accounts.to_vec());
+
+                transfer_checked(cpi_ctx, amount_b, mint_b.decimals)?;
+            }
+            (None, None, None) => {
+                // the maker asked for SOL, pay it straight into their wallet
+                let accounts = system_program::Transfer {
+                    from: self.taker.to_account_info(),
+                    to: self.maker.to_account_info()
+                };
+
+                let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+                system_program::transfer(cpi_ctx, amount_b)?;
+            }
+            _ => return err!(EscrowError::MissingTokenAccounts)
+        }
+
+        self.escrow.receive_amount -= net_b;
+
+        Ok(())
     }
 
     // Send money from vault to taker
-    pub fn withdraw(&mut self) -> Result<()> {
-        let signer_seeds: [&[&[u8]]; 1] = [
-            &[
-                b"escrow",
-                self.maker.to_account_info().key.as_ref(),
-                &self.escrow.seed.to_le_bytes()[..],
-                &[self.escrow.bump]
-            ]
-        ];
-
-        let accounts = Transfer {
-            from: self.vault.to_account_info(),
-            to: self.taker_ata_a.to_account_info(),
-            authority: self.escrow.to_account_info()
-        };
-
-        // ! CpiContext::new_with_signer - use when signing on behalf of a PDA
-        let cpi_ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            accounts,
-            &signer_seeds
-        );
-
-        transfer(cpi_ctx, self.vault.amount)
+    pub fn withdraw(&mut self, remaining_accounts: &[AccountInfo<'info>], amount_a: u64) -> Result<()> {
+        match (&self.mint_a, &self.taker_ata_a, &self.vault) {
+            (Some(mint_a), Some(taker_ata_a), Some(vault)) => {
+                // the last fill sweeps the vault so close_vault can't be blocked by someone sending it extra tokens
+                let amount_a = if self.escrow.receive_amount == 0 { vault.amount } else { amount_a };
+
+                let signer_seeds: [&[&[u8]]; 1] = [
+                    &[
+                        b"escrow",
+                        self.maker.to_account_info().key.as_ref(),
+                        &self.escrow.seed.to_le_bytes()[..],
+                        &[self.escrow.bump]
+                    ]
+                ];
+
+                let accounts = TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: mint_a.to_account_info(),
+                    to: taker_ata_a.to_account_info(),
+                    authority: self.escrow.to_account_info()
+                };
+
+                // ! CpiContext::new_with_signer - use when signing on behalf of a PDA
+                let cpi_ctx = CpiContext::new_with_signer(
+                    self.token_program_a.to_account_info(),
+                    accounts,
+                    &signer_seeds
+                ).with_remaining_accounts(remaining_accounts.to_vec());
+
+                transfer_checked(cpi_ctx, amount_a, mint_a.decimals)?;
+
+                self.escrow.deposit_amount = self.escrow.deposit_amount.saturating_sub(amount_a);
+            }
+            (None, None, None) => {
+                // the SOL is in the escrow pda, which we own, so we can move lamports without a cpi
+                // (the system program can't transfer from an account that has data)
+                **self.escrow.to_account_info().try_borrow_mut_lamports()? -= amount_a;
+                **self.taker.to_account_info().try_borrow_mut_lamports()? += amount_a;
+
+                self.escrow.deposit_amount -= amount_a;
+            }
+            _ => return err!(EscrowError::MissingTokenAccounts)
+        }
+
+        Ok(())
     }
 
     // Close the vault
     pub fn close_vault(&mut self) -> Result<()> {
+        let (Some(mint_a), Some(vault)) = (&self.mint_a, &self.vault) else {
+            return Ok(()); // a SOL deposit has no vault to close
+        };
+
+        harvest_withheld(self.token_program_a.to_account_info(), mint_a.to_account_info(), vault.to_account_info())?;
+
         let signer_seeds: [&[&[u8]]; 1] = [
             &[
                 b"escrow",
@@ -116,17 +192,24 @@ impl<'info> Take<'info> {
         ];
 
         let close_accounts = CloseAccount {
-            account: self.vault.to_account_info(),
+            account: vault.to_account_info(),
             destination: self.taker.to_account_info(), // where to send the lamports from closing this account
             authority: self.escrow.to_account_info()
         };
 
         let cpi_ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
+            self.token_program_a.to_account_info(),
             close_accounts,
             &signer_seeds
         );
 
         close_account(cpi_ctx)
     }
-}
\ No newline at end of file
+
+    // can't use close = taker on the escrow anymore because a partial fill has to keep it open
+    pub fn close_escrow(&mut self) -> Result<()> {
+        // setting the maker as destination would be ok too
+        // we choose to give that to the taker to offset that they will likely have to pay for creating maker's ata for mint_b
+        self.escrow.close(self.taker.to_account_info())
+    }
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::accessor,
+    token_interface::{TokenInterface, TransferChecked, CloseAccount, close_account},
+    associated_token::{AssociatedToken, Create, create_idempotent, get_associated_token_address_with_program_id}
+};
+use crate::{state::{Basket, ACCOUNTS_PER_LEG}, error::EscrowError, transfer::{mint_decimals, transfer_checked, harvest_withheld}};
+
+// remaining_accounts, requested legs first then offered legs, each in the basket's order:
+// requested: [mint, taker token account, maker ata]
+// offered:   [mint (writable), vault, taker ata]
+// and after all the legs, the accounts any of the mints' transfer hooks need
+#[derive(Accounts)]
+pub struct TakeBasket <'info>{
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = taker, // same as the single escrow, offsets the atas the taker may have to create
+        seeds = [b"basket", maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump
+    )]
+    pub basket: Account<'info, Basket>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> TakeBasket<'info> {
+    // one check up front so pay and release can just slice their part, the hook accounts go to both
+    pub fn split_legs<'a>(
+        &self,
+        remaining_accounts: &'a [AccountInfo<'info>]
+    ) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+        let requested = self.basket.requested.len() * ACCOUNTS_PER_LEG;
+        let offered = self.basket.offered.len() * ACCOUNTS_PER_LEG;
+        require!(remaining_accounts.len() >= requested + offered, EscrowError::WrongLegAccounts);
+
+        let (requested, rest) = remaining_accounts.split_at(requested);
+        let (offered, hook_accounts) = rest.split_at(offered);
+        Ok((requested, offered, hook_accounts))
+    }
+
+    // the destination has to be the owner's ata for the leg's mint, created if it's missing
+    fn destination_ata(&self, ata: &AccountInfo<'info>, owner: &AccountInfo<'info>, mint: &AccountInfo<'info>) -> Result<()> {
+        require_keys_eq!(ata.key(), get_associated_token_address_with_program_id(owner.key, mint.key, self.token_program.key), EscrowError::WrongLegAccounts);
+
+        let create_accounts = Create {
+            payer: self.taker.to_account_info(),
+            associated_token: ata.to_account_info(),
+            authority: owner.to_account_info(),
+            mint: mint.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info()
+        };
+
+        create_idempotent(CpiContext::new(self.associated_token_program.to_account_info(), create_accounts))
+    }
+
+    // Send every requested leg from taker to maker
+    pub fn pay(&mut self, legs: &[AccountInfo<'info>], hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        for (leg, accounts) in self.basket.requested.iter().zip(legs.chunks(ACCOUNTS_PER_LEG)) {
+            let [mint, taker_ata, maker_ata] = accounts else {
+                return err!(EscrowError::WrongLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), leg.mint, EscrowError::WrongLegAccounts);
+            self.destination_ata(maker_ata, &self.maker.to_account_info(), mint)?;
+
+            // maker_ata is pinned to the leg's mint, and the token program won't move tokens between
+            // accounts of different mints, so the taker can't pay with something else
+            let accounts = TransferChecked {
+                from: taker_ata.to_account_info(),
+                mint: mint.to_account_info(),
+                to: maker_ata.to_account_info(),
+                authority: self.taker.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts)
+                .with_remaining_accounts(hook_accounts.to_vec());
+
+            transfer_checked(cpi_ctx, leg.amount, mint_decimals(mint)?)?;
+        }
+
+        Ok(())
+    }
+
+    // Empty and close every vault into the taker's atas
+    pub fn release(&mut self, legs: &[AccountInfo<'info>], hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let seed = self.basket.seed.to_le_bytes();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"basket",
+                self.maker.to_account_info().key.as_ref(),
+                &seed[..],
+                &[self.basket.bump]
+            ]
+        ];
+
+        for (leg, accounts) in self.basket.offered.iter().zip(legs.chunks(ACCOUNTS_PER_LEG)) {
+            let [mint, vault, taker_ata] = accounts else {
+                return err!(EscrowError::WrongLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), leg.mint, EscrowError::WrongLegAccounts);
+            require_keys_eq!(vault.key(), get_associated_token_address_with_program_id(&self.basket.key(), &leg.mint, self.token_program.key), EscrowError::WrongLegAccounts);
+            self.destination_ata(taker_ata, &self.taker.to_account_info(), mint)?;
+
+            // sweep whatever is in there so the vault can be closed
+            let amount = accessor::amount(vault)?;
+
+            let accounts = TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: taker_ata.to_account_info(),
+                authority: self.basket.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                accounts,
+                &signer_seeds
+            ).with_remaining_accounts(hook_accounts.to_vec());
+
+            transfer_checked(cpi_ctx, amount, mint_decimals(mint)?)?;
+
+            harvest_withheld(self.token_program.to_account_info(), mint.to_account_info(), vault.to_account_info())?;
+
+            let close_accounts = CloseAccount {
+                account: vault.to_account_info(),
+                destination: self.taker.to_account_info(),
+                authority: self.basket.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                close_accounts,
+                &signer_seeds
+            );
+
+            close_account(cpi_ctx)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Fill is larger than what the escrow still asks for")]
+    FillTooLarge,
+    #[msg("Fill is too small to release any of the deposit")]
+    FillTooSmall,
+    #[msg("Math overflow")]
+    Overflow,
+    #[msg("Expiry must be in the future, or 0 for no expiry")]
+    InvalidExpiry,
+    #[msg("Escrow has expired")]
+    Expired,
+    #[msg("Escrow has not expired yet")]
+    NotExpired,
+    #[msg("This escrow can only be taken by its agreed counterparty")]
+    TakerNotAllowed,
+    #[msg("A token leg needs its mint and token accounts, a SOL leg none of them")]
+    MissingTokenAccounts,
+    #[msg("Mint doesn't match the escrow")]
+    WrongMint,
+    #[msg("A basket needs at least one leg on each side")]
+    EmptyBasket,
+    #[msg("Too many legs in the basket")]
+    TooManyLegs,
+    #[msg("The same mint appears twice on one side of the basket")]
+    DuplicateMint,
+    #[msg("Remaining accounts don't match the basket legs")]
+    WrongLegAccounts,
+}
@@ -5,6 +5,12 @@ pub mod contexts;
 use contexts::*;
 
 pub mod state;
+use state::BasketLeg;
+
+pub mod error;
+use error::EscrowError;
+
+pub mod transfer;
 
 declare_id!("9obik1Tntr8BDeoLj7KwnG7e6uQSDeqpX3s8WMeFUYeJ");
 
@@ -13,20 +19,76 @@ pub mod anchor_escrow {
     use super::*;
 
     // seed to add some entropy to the ata so that we can have multiple
-    pub fn make(ctx: Context<Make>, seed: u64, deposit_amount: u64, receive_amount: u64) -> Result<()> {
-        ctx.accounts.deposit(deposit_amount)?;
-        ctx.accounts.save(seed, receive_amount, &ctx.bumps)
+    pub fn make<'info>(
+        ctx: Context<'_, '_, '_, 'info, Make<'info>>,
+        seed: u64,
+        deposit_amount: u64,
+        receive_amount: u64,
+        expires_at: i64,
+        taker: Option<Pubkey>
+    ) -> Result<()> {
+        // with a token-2022 transfer fee less than deposit_amount arrives, the escrow only offers what did
+        let deposit_amount = ctx.accounts.deposit(ctx.remaining_accounts, deposit_amount)?;
+        ctx.accounts.save(seed, deposit_amount, receive_amount, expires_at, taker, &ctx.bumps)
     }
 
-    pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        ctx.accounts.refund()?;
+    // remaining accounts on the single escrow instructions are only for transfer hooks
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+        ctx.accounts.refund(ctx.remaining_accounts)?;
         // We now are gonna close the vault too
         ctx.accounts.close_vault()
     }
 
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        ctx.accounts.deposit()?;
-        ctx.accounts.withdraw()?;
-        ctx.accounts.close_vault()
+    pub fn take<'info>(ctx: Context<'_, '_, '_, 'info, Take<'info>>) -> Result<()> {
+        // a full take is just a partial take of everything that's left, plus whatever transfer fee mint_b takes
+        let amount_b = ctx.accounts.gross_receive_amount()?;
+        take_partial(ctx, amount_b)
+    }
+
+    pub fn take_partial<'info>(ctx: Context<'_, '_, '_, 'info, Take<'info>>, amount_b: u64) -> Result<()> {
+        require!(!ctx.accounts.escrow.is_expired(Clock::get()?.unix_timestamp), EscrowError::Expired);
+
+        // priced on what reaches the maker, the same way make only offers what reached the vault
+        let net_b = ctx.accounts.net_b(amount_b)?;
+        let amount_a = ctx.accounts.escrow.release_for(net_b)?;
+        ctx.accounts.deposit(ctx.remaining_accounts, amount_b, net_b)?;
+        ctx.accounts.withdraw(ctx.remaining_accounts, amount_a)?;
+
+        // only the fill that completes the offer cleans up
+        if ctx.accounts.escrow.receive_amount == 0 {
+            ctx.accounts.close_vault()?;
+            ctx.accounts.close_escrow()?;
+        }
+
+        Ok(())
+    }
+
+    // anyone can clean up an expired offer, the maker gets everything back minus the tip
+    pub fn expire<'info>(ctx: Context<'_, '_, '_, 'info, Expire<'info>>) -> Result<()> {
+        ctx.accounts.refund(ctx.remaining_accounts)?;
+        ctx.accounts.close_vault()?;
+        ctx.accounts.close_escrow()
+    }
+
+    // same deal with several tokens on each side, the per mint accounts come in remaining_accounts
+    pub fn make_basket<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeBasket<'info>>,
+        seed: u64,
+        offered: Vec<BasketLeg>,
+        requested: Vec<BasketLeg>
+    ) -> Result<()> {
+        ctx.accounts.save(seed, offered, requested, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)
+    }
+
+    // all legs settle in this one instruction, if any of them fails nothing moves
+    pub fn take_basket<'info>(ctx: Context<'_, '_, '_, 'info, TakeBasket<'info>>) -> Result<()> {
+        let (requested, offered, hook_accounts) = ctx.accounts.split_legs(ctx.remaining_accounts)?;
+        ctx.accounts.pay(requested, hook_accounts)?;
+        ctx.accounts.release(offered, hook_accounts)
+    }
+
+    pub fn refund_basket<'info>(ctx: Context<'_, '_, '_, 'info, RefundBasket<'info>>) -> Result<()> {
+        ctx.accounts.refund(ctx.remaining_accounts)
     }
 }
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use crate::error::EscrowError;
+
+// every leg needs 3 remaining accounts on take, so this keeps a full basket inside one transaction
+pub const MAX_BASKET_LEGS: usize = 4;
+
+// remaining accounts passed per leg, see the basket contexts for what goes in them
+pub const ACCOUNTS_PER_LEG: usize = 3;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub amount: u64
+}
+
+// like an Escrow but with several mints on each side, settled all at once
+#[account]
+pub struct Basket {
+    pub seed: u64,
+    pub offered: Vec<BasketLeg>, // what the maker deposited, one vault per mint
+    pub requested: Vec<BasketLeg>, // what the taker has to pay the maker
+    pub bump: u8
+}
+
+impl Space for Basket {
+    const INIT_SPACE: usize = 8 + 8 + 2 * (4 + (32 + 8) * MAX_BASKET_LEGS) + 1;
+}
+
+impl Basket {
+    pub fn check_legs(legs: &[BasketLeg]) -> Result<()> {
+        require!(!legs.is_empty(), EscrowError::EmptyBasket);
+        require!(legs.len() <= MAX_BASKET_LEGS, EscrowError::TooManyLegs);
+
+        for (i, leg) in legs.iter().enumerate() {
+            require!(leg.amount > 0, EscrowError::ZeroAmount);
+            // two legs with the same mint would share a vault
+            require!(legs[..i].iter().all(|other| other.mint != leg.mint), EscrowError::DuplicateMint);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(mint: Pubkey, amount: u64) -> BasketLeg {
+        BasketLeg { mint, amount }
+    }
+
+    #[test]
+    fn accepts_distinct_legs() {
+        let legs: Vec<_> = (0..MAX_BASKET_LEGS).map(|_| leg(Pubkey::new_unique(), 1)).collect();
+        assert!(Basket::check_legs(&legs).is_ok());
+    }
+
+    #[test]
+    fn rejects_empty_and_too_many() {
+        assert_eq!(Basket::check_legs(&[]).unwrap_err(), EscrowError::EmptyBasket.into());
+
+        let legs: Vec<_> = (0..=MAX_BASKET_LEGS).map(|_| leg(Pubkey::new_unique(), 1)).collect();
+        assert_eq!(Basket::check_legs(&legs).unwrap_err(), EscrowError::TooManyLegs.into());
+    }
+
+    #[test]
+    fn rejects_zero_amount() {
+        let legs = [leg(Pubkey::new_unique(), 5), leg(Pubkey::new_unique(), 0)];
+        assert_eq!(Basket::check_legs(&legs).unwrap_err(), EscrowError::ZeroAmount.into());
+    }
+
+    #[test]
+    fn rejects_duplicate_mint() {
+        let mint = Pubkey::new_unique();
+        let legs = [leg(mint, 5), leg(Pubkey::new_unique(), 5), leg(mint, 7)];
+        assert_eq!(Basket::check_legs(&legs).unwrap_err(), EscrowError::DuplicateMint.into());
+    }
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::error::EscrowError;
+
+#[account]
+pub struct Escrow {
+    pub seed: u64,
+    pub mint_a: Option<Pubkey>, // None is native SOL
+    pub mint_b: Option<Pubkey>,
+    pub deposit_amount: u64, // what's left in the vault, goes down with every partial fill
+    pub receive_amount: u64, // what the maker still has to receive (after any transfer fee), the escrow is closed when it hits 0
+    pub expires_at: i64, // unix timestamp after which it can't be taken anymore, 0 means never
+    pub taker: Option<Pubkey>, // a private deal only this key can take, None means anyone
+    pub bump: u8, // we don't have to save the keys but it's good practice
+    pub vault_bump: u8 // what if we don't add this -> we could but we're saving compute
+}
+
+impl Space for Escrow {
+    const INIT_SPACE: usize = (1 + 32) + 8 + (1 + 32) + 1 + 1 + 8 + 8 + 8 + 8 + (1 + 32); // anchor adds a discriminator of 8 bytes
+}
+
+// paid out of the escrow's rent to whoever cranks expire, the maker gets the rest
+pub const EXPIRE_TIP: u64 = 100_000;
+
+impl Escrow {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+
+    // how much of mint_a a fill of amount_b releases, same price as the original offer
+    // rounds down so the maker never gives away more than they asked a price for
+    pub fn release_for(&self, amount_b: u64) -> Result<u64> {
+        require!(amount_b > 0, EscrowError::ZeroAmount);
+        require!(amount_b <= self.receive_amount, EscrowError::FillTooLarge);
+
+        if amount_b == self.receive_amount {
+            return Ok(self.deposit_amount); // the last fill takes whatever rounding left behind
+        }
+
+        let amount_a = (self.deposit_amount as u128)
+            .checked_mul(amount_b as u128)
+            .ok_or(EscrowError::Overflow)?
+            / self.receive_amount as u128;
+        require!(amount_a > 0, EscrowError::FillTooSmall);
+
+        Ok(amount_a as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // only the remaining amounts matter to release_for
+    fn escrow(deposit_amount: u64, receive_amount: u64) -> Escrow {
+        Escrow {
+            seed: 0,
+            mint_a: None,
+            mint_b: None,
+            deposit_amount,
+            receive_amount,
+            expires_at: 0,
+            taker: None,
+            bump: 0,
+            vault_bump: 0
+        }
+    }
+
+    #[test]
+    fn partial_fills_round_down() {
+        // 100 of mint_a for 30 of mint_b, so 3.33.. a per b
+        assert_eq!(escrow(100, 30).release_for(10).unwrap(), 33);
+        assert_eq!(escrow(100, 30).release_for(29).unwrap(), 96);
+    }
+
+    #[test]
+    fn last_fill_takes_the_rest() {
+        // after two fills of 10 the rounding left 34 behind for the last 10
+        assert_eq!(escrow(34, 10).release_for(10).unwrap(), 34);
+        assert_eq!(escrow(100, 30).release_for(30).unwrap(), 100);
+    }
+
+    #[test]
+    fn rejects_bad_fills() {
+        assert_eq!(escrow(100, 30).release_for(0).unwrap_err(), EscrowError::ZeroAmount.into());
+        assert_eq!(escrow(100, 30).release_for(31).unwrap_err(), EscrowError::FillTooLarge.into());
+        // 1 of 1000 is worth a tenth of a token, nothing to release
+        assert_eq!(escrow(100, 1000).release_for(1).unwrap_err(), EscrowError::FillTooSmall.into());
+    }
+
+    #[test]
+    fn large_amounts_dont_overflow() {
+        assert_eq!(escrow(u64::MAX, u64::MAX).release_for(u64::MAX - 1).unwrap(), u64::MAX - 1);
+    }
+}
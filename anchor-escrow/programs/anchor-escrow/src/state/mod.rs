@@ -0,0 +1,6 @@
+// now that there's more than the Escrow struct it gets a state folder
+pub mod escrow;
+pub use escrow::*;
+
+pub mod basket;
+pub use basket::*;
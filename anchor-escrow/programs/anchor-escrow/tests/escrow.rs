@@ -0,0 +1,528 @@
+use anchor_escrow::{error::EscrowError, state::{BasketLeg, EXPIRE_TIP}};
+use anchor_lang::{prelude::*, InstructionData, system_program, solana_program::entrypoint::ProgramResult};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{ExtensionType, StateWithExtensions, transfer_fee::instruction::initialize_transfer_fee_config},
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    system_instruction,
+};
+
+// same as the vault tests, anchor's entrypoint wants the account infos to outlive the accounts slice
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_escrow::entry(program_id, accounts, data)
+}
+
+async fn setup() -> (ProgramTestContext, Keypair, Keypair) {
+    let program_test = ProgramTest::new("anchor_escrow", anchor_escrow::ID, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let (maker, taker) = (Keypair::new(), Keypair::new());
+    let fund = [
+        system_instruction::transfer(&context.payer.pubkey(), &maker.pubkey(), 10 * LAMPORTS_PER_SOL),
+        system_instruction::transfer(&context.payer.pubkey(), &taker.pubkey(), 10 * LAMPORTS_PER_SOL),
+    ];
+    send(&mut context, &fund, &[]).await.unwrap();
+
+    (context, maker, taker)
+}
+
+async fn send(context: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) -> std::result::Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+
+    context.banks_client.process_transaction(tx).await
+}
+
+fn assert_custom_error(result: std::result::Result<(), BanksClientError>, code: u32) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => assert_eq!(actual, code),
+        err => panic!("expected custom error {code}, got {err:?}"),
+    }
+}
+
+fn escrow_pda(maker: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow", maker.as_ref(), &seed.to_le_bytes()], &anchor_escrow::ID).0
+}
+
+fn vault_pda(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", escrow.as_ref()], &anchor_escrow::ID).0
+}
+
+fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+// the associated token program's create instruction, built by hand to avoid another dev dependency
+fn create_ata_ix(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_spl::associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(ata(owner, mint, token_program), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: vec![],
+    }
+}
+
+// a mint on either token program with `amount` minted to `holder`, fee_bps adds a token-2022 transfer fee
+async fn create_mint(context: &mut ProgramTestContext, token_program: Pubkey, holder: &Pubkey, amount: u64, fee_bps: Option<u16>) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    let extensions = if fee_bps.is_some() { vec![ExtensionType::TransferFeeConfig] } else { vec![] };
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap().minimum_balance(space);
+
+    let mut instructions = vec![system_instruction::create_account(&payer, &mint.pubkey(), rent, space as u64, &token_program)];
+    if let Some(fee_bps) = fee_bps {
+        instructions.push(initialize_transfer_fee_config(&token_program, &mint.pubkey(), None, None, fee_bps, u64::MAX).unwrap());
+    }
+    instructions.extend([
+        spl_token_2022::instruction::initialize_mint2(&token_program, &mint.pubkey(), &payer, None, 6).unwrap(),
+        create_ata_ix(&payer, holder, &mint.pubkey(), &token_program),
+        spl_token_2022::instruction::mint_to(&token_program, &mint.pubkey(), &ata(holder, &mint.pubkey(), &token_program), &payer, &[], amount).unwrap(),
+    ]);
+    send(context, &instructions, &[&mint]).await.unwrap();
+
+    mint.pubkey()
+}
+
+async fn token_balance(context: &mut ProgramTestContext, account: Pubkey) -> Option<u64> {
+    let account = context.banks_client.get_account(account).await.unwrap()?;
+    Some(StateWithExtensions::<TokenAccount>::unpack(&account.data).unwrap().base.amount)
+}
+
+// None on a leg makes it a native SOL leg
+#[derive(Clone, Copy)]
+struct Legs {
+    mint_a: Option<Pubkey>,
+    mint_b: Option<Pubkey>,
+    token_program_a: Pubkey,
+    token_program_b: Pubkey,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn make_ix(maker: &Pubkey, seed: u64, legs: Legs, deposit_amount: u64, receive_amount: u64, expires_at: i64, taker: Option<Pubkey>) -> Instruction {
+    let escrow = escrow_pda(maker, seed);
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts: anchor_escrow::accounts::Make {
+            maker: *maker,
+            mint_a: legs.mint_a,
+            mint_b: legs.mint_b,
+            maker_ata_a: legs.mint_a.map(|mint| ata(maker, &mint, &legs.token_program_a)),
+            vault: legs.mint_a.map(|_| vault_pda(&escrow)),
+            escrow,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program_a: legs.token_program_a,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_escrow::instruction::Make { seed, deposit_amount, receive_amount, expires_at, taker }.data(),
+    }
+}
+
+// amount_b None is a full take
+fn take_ix(taker: &Pubkey, maker: &Pubkey, seed: u64, legs: Legs, amount_b: Option<u64>) -> Instruction {
+    let escrow = escrow_pda(maker, seed);
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts: anchor_escrow::accounts::Take {
+            taker: *taker,
+            maker: *maker,
+            mint_a: legs.mint_a,
+            mint_b: legs.mint_b,
+            taker_ata_a: legs.mint_a.map(|mint| ata(taker, &mint, &legs.token_program_a)),
+            taker_ata_b: legs.mint_b.map(|mint| ata(taker, &mint, &legs.token_program_b)),
+            maker_ata_b: legs.mint_b.map(|mint| ata(maker, &mint, &legs.token_program_b)),
+            escrow,
+            vault: legs.mint_a.map(|_| vault_pda(&escrow)),
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program_a: legs.token_program_a,
+            token_program_b: legs.token_program_b,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: match amount_b {
+            Some(amount_b) => anchor_escrow::instruction::TakePartial { amount_b }.data(),
+            None => anchor_escrow::instruction::Take {}.data(),
+        },
+    }
+}
+
+fn expire_ix(cranker: &Pubkey, maker: &Pubkey, seed: u64, legs: Legs) -> Instruction {
+    let escrow = escrow_pda(maker, seed);
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts: anchor_escrow::accounts::Expire {
+            cranker: *cranker,
+            maker: *maker,
+            mint_a: legs.mint_a,
+            maker_ata_a: legs.mint_a.map(|mint| ata(maker, &mint, &legs.token_program_a)),
+            vault: legs.mint_a.map(|_| vault_pda(&escrow)),
+            escrow,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program_a: legs.token_program_a,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_escrow::instruction::Expire {}.data(),
+    }
+}
+
+async fn escrow_state(context: &mut ProgramTestContext, escrow: Pubkey) -> anchor_escrow::state::Escrow {
+    let account = context.banks_client.get_account(escrow).await.unwrap().unwrap();
+    anchor_escrow::state::Escrow::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+async fn balance(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    context.banks_client.get_balance(address).await.unwrap()
+}
+
+fn basket_pda(maker: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"basket", maker.as_ref(), &seed.to_le_bytes()], &anchor_escrow::ID).0
+}
+
+// the per leg remaining accounts, `writable_mint` for the legs that may harvest withheld fees
+fn leg_accounts(mint: Pubkey, first: Pubkey, second: Pubkey, writable_mint: bool) -> [AccountMeta; 3] {
+    let mint = if writable_mint { AccountMeta::new(mint, false) } else { AccountMeta::new_readonly(mint, false) };
+    [mint, AccountMeta::new(first, false), AccountMeta::new(second, false)]
+}
+
+// the remaining accounts follow `mints`, so passing them in another order than the legs tests the checks
+fn make_basket_ix(maker: &Pubkey, seed: u64, offered: Vec<BasketLeg>, requested: Vec<BasketLeg>, mints: &[Pubkey]) -> Instruction {
+    let basket = basket_pda(maker, seed);
+    let token_program = anchor_spl::token::ID;
+    let mut accounts = anchor_escrow::accounts::MakeBasket {
+        maker: *maker,
+        basket,
+        associated_token_program: anchor_spl::associated_token::ID,
+        token_program,
+        system_program: system_program::ID,
+    }.to_account_metas(None);
+    for mint in mints {
+        accounts.extend(leg_accounts(*mint, ata(maker, mint, &token_program), ata(&basket, mint, &token_program), false));
+    }
+
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts,
+        data: anchor_escrow::instruction::MakeBasket { seed, offered, requested }.data(),
+    }
+}
+
+fn take_basket_ix(taker: &Pubkey, maker: &Pubkey, seed: u64, requested: &[Pubkey], offered: &[Pubkey]) -> Instruction {
+    let basket = basket_pda(maker, seed);
+    let token_program = anchor_spl::token::ID;
+    let mut accounts = anchor_escrow::accounts::TakeBasket {
+        taker: *taker,
+        maker: *maker,
+        basket,
+        associated_token_program: anchor_spl::associated_token::ID,
+        token_program,
+        system_program: system_program::ID,
+    }.to_account_metas(None);
+    for mint in requested {
+        accounts.extend(leg_accounts(*mint, ata(taker, mint, &token_program), ata(maker, mint, &token_program), false));
+    }
+    for mint in offered {
+        accounts.extend(leg_accounts(*mint, ata(&basket, mint, &token_program), ata(taker, mint, &token_program), true));
+    }
+
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts,
+        data: anchor_escrow::instruction::TakeBasket {}.data(),
+    }
+}
+
+// vaults lets a test hand in some other token account where the basket's vault should go
+fn refund_basket_ix(maker: &Pubkey, seed: u64, offered: &[Pubkey], vaults: &[Pubkey]) -> Instruction {
+    let basket = basket_pda(maker, seed);
+    let token_program = anchor_spl::token::ID;
+    let mut accounts = anchor_escrow::accounts::RefundBasket {
+        maker: *maker,
+        basket,
+        token_program,
+        system_program: system_program::ID,
+    }.to_account_metas(None);
+    for (mint, vault) in offered.iter().zip(vaults) {
+        accounts.extend(leg_accounts(*mint, *vault, ata(maker, mint, &token_program), true));
+    }
+
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts,
+        data: anchor_escrow::instruction::RefundBasket {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn token_2022_escrow_with_transfer_fee() {
+    let (mut context, maker, taker) = setup().await;
+    let token_program = spl_token_2022::ID;
+
+    // 1% on every transfer of mint_a
+    let mint_a = create_mint(&mut context, token_program, &maker.pubkey(), 10_000, Some(100)).await;
+    let mint_b = create_mint(&mut context, token_program, &taker.pubkey(), 500, None).await;
+    let legs = Legs { mint_a: Some(mint_a), mint_b: Some(mint_b), token_program_a: token_program, token_program_b: token_program };
+    let escrow = escrow_pda(&maker.pubkey(), 0);
+
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, legs, 10_000, 500, 0, None)], &[&maker]).await.unwrap();
+
+    // the escrow only offers what arrived after the fee
+    assert_eq!(escrow_state(&mut context, escrow).await.deposit_amount, 9_900);
+
+    // the vault has fees withheld in it, closing it on the last fill has to harvest them first
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, None)], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &mint_a, &token_program)).await, Some(9_801));
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &mint_b, &token_program)).await, Some(500));
+    assert!(context.banks_client.get_account(vault_pda(&escrow)).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(escrow).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn legacy_mint_for_a_token_2022_mint() {
+    let (mut context, maker, taker) = setup().await;
+    let mint_a = create_mint(&mut context, anchor_spl::token::ID, &maker.pubkey(), 100, None).await;
+    let mint_b = create_mint(&mut context, spl_token_2022::ID, &taker.pubkey(), 30, None).await;
+    let legs = Legs { mint_a: Some(mint_a), mint_b: Some(mint_b), token_program_a: anchor_spl::token::ID, token_program_b: spl_token_2022::ID };
+
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, legs, 100, 30, 0, None)], &[&maker]).await.unwrap();
+
+    // each leg's accounts are derived and checked against its own token program
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, None)], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &mint_a, &anchor_spl::token::ID)).await, Some(100));
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &mint_b, &spl_token_2022::ID)).await, Some(30));
+}
+
+#[tokio::test]
+async fn transfer_fee_on_mint_b_is_priced_net() {
+    let (mut context, maker, taker) = setup().await;
+    let mint_a = create_mint(&mut context, anchor_spl::token::ID, &maker.pubkey(), 100, None).await;
+    // 1% on every transfer of mint_b
+    let mint_b = create_mint(&mut context, spl_token_2022::ID, &taker.pubkey(), 1_100, Some(100)).await;
+    let legs = Legs { mint_a: Some(mint_a), mint_b: Some(mint_b), token_program_a: anchor_spl::token::ID, token_program_b: spl_token_2022::ID };
+    let escrow = escrow_pda(&maker.pubkey(), 0);
+    let maker_ata_b = ata(&maker.pubkey(), &mint_b, &spl_token_2022::ID);
+
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, legs, 100, 1_000, 0, None)], &[&maker]).await.unwrap();
+
+    // 500 sent, 495 arrives, so the fill only counts as 495 of the 1000
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, Some(500))], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, maker_ata_b).await, Some(495));
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &mint_a, &anchor_spl::token::ID)).await, Some(49));
+    assert_eq!(escrow_state(&mut context, escrow).await.receive_amount, 505);
+
+    // the full take sends 511 so the 505 left still arrives after the fee
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, None)], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, maker_ata_b).await, Some(1_000));
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &mint_b, &spl_token_2022::ID)).await, Some(89));
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &mint_a, &anchor_spl::token::ID)).await, Some(100));
+    assert!(context.banks_client.get_account(escrow).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn make_rejects_asking_for_nothing() {
+    let (mut context, maker, _) = setup().await;
+    let sol = Legs { mint_a: None, mint_b: None, token_program_a: spl_token_2022::ID, token_program_b: spl_token_2022::ID };
+
+    // take would have nothing to pay and no price to release the deposit at
+    let result = send(&mut context, &[make_ix(&maker.pubkey(), 0, sol, LAMPORTS_PER_SOL, 0, 0, None)], &[&maker]).await;
+    assert_custom_error(result, EscrowError::ZeroAmount.into());
+}
+
+#[tokio::test]
+async fn partial_fills_round_down_and_the_last_one_closes() {
+    let (mut context, maker, taker) = setup().await;
+    let token_program = anchor_spl::token::ID;
+    let mint_a = create_mint(&mut context, token_program, &maker.pubkey(), 100, None).await;
+    let mint_b = create_mint(&mut context, token_program, &taker.pubkey(), 30, None).await;
+    let legs = Legs { mint_a: Some(mint_a), mint_b: Some(mint_b), token_program_a: token_program, token_program_b: token_program };
+    let escrow = escrow_pda(&maker.pubkey(), 0);
+    let taker_ata_a = ata(&taker.pubkey(), &mint_a, &token_program);
+
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, legs, 100, 30, 0, None)], &[&maker]).await.unwrap();
+
+    // 10 of 30 is a third of 100, rounded down
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, Some(10))], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, taker_ata_a).await, Some(33));
+    let state = escrow_state(&mut context, escrow).await;
+    assert_eq!((state.deposit_amount, state.receive_amount), (67, 20));
+
+    let result = send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, Some(21))], &[&taker]).await;
+    assert_custom_error(result, EscrowError::FillTooLarge.into());
+
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, Some(10))], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, taker_ata_a).await, Some(66));
+
+    // the full take of what's left gets the rounding dust too
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, None)], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, taker_ata_a).await, Some(100));
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &mint_b, &token_program)).await, Some(30));
+    assert!(context.banks_client.get_account(vault_pda(&escrow)).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(escrow).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn expired_escrow_is_cranked_for_a_tip() {
+    let (mut context, maker, taker) = setup().await;
+    let token_program = anchor_spl::token::ID;
+    let mint_a = create_mint(&mut context, token_program, &maker.pubkey(), 100, None).await;
+    let mint_b = create_mint(&mut context, token_program, &taker.pubkey(), 30, None).await;
+    let legs = Legs { mint_a: Some(mint_a), mint_b: Some(mint_b), token_program_a: token_program, token_program_b: token_program };
+    let escrow = escrow_pda(&maker.pubkey(), 0);
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, legs, 100, 30, clock.unix_timestamp + 3_600, None)], &[&maker]).await.unwrap();
+
+    // the taker cranks it, anyone could
+    let result = send(&mut context, &[expire_ix(&taker.pubkey(), &maker.pubkey(), 0, legs)], &[&taker]).await;
+    assert_custom_error(result, EscrowError::NotExpired.into());
+
+    clock.unix_timestamp += 3_600;
+    context.set_sysvar(&clock);
+
+    let result = send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, legs, None)], &[&taker]).await;
+    assert_custom_error(result, EscrowError::Expired.into());
+
+    let cranker_before = balance(&mut context, taker.pubkey()).await;
+    let maker_before = balance(&mut context, maker.pubkey()).await;
+    let rent = balance(&mut context, escrow).await + balance(&mut context, vault_pda(&escrow)).await;
+
+    send(&mut context, &[expire_ix(&taker.pubkey(), &maker.pubkey(), 0, legs)], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &mint_a, &token_program)).await, Some(100));
+    assert_eq!(balance(&mut context, taker.pubkey()).await, cranker_before + EXPIRE_TIP);
+    assert_eq!(balance(&mut context, maker.pubkey()).await, maker_before + rent - EXPIRE_TIP);
+    assert!(context.banks_client.get_account(escrow).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn private_escrow_only_takes_the_agreed_taker() {
+    let (mut context, maker, taker) = setup().await;
+    let outsider = Keypair::new();
+    let fund = system_instruction::transfer(&context.payer.pubkey(), &outsider.pubkey(), 10 * LAMPORTS_PER_SOL);
+    send(&mut context, &[fund], &[]).await.unwrap();
+
+    let sol = Legs { mint_a: None, mint_b: None, token_program_a: anchor_spl::token::ID, token_program_b: anchor_spl::token::ID };
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, sol, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL, 0, Some(taker.pubkey()))], &[&maker]).await.unwrap();
+
+    let result = send(&mut context, &[take_ix(&outsider.pubkey(), &maker.pubkey(), 0, sol, None)], &[&outsider]).await;
+    assert_custom_error(result, EscrowError::TakerNotAllowed.into());
+
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, sol, None)], &[&taker]).await.unwrap();
+    assert!(context.banks_client.get_account(escrow_pda(&maker.pubkey(), 0)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn native_sol_legs() {
+    let (mut context, maker, taker) = setup().await;
+    let token_program = anchor_spl::token::ID;
+    let mint = create_mint(&mut context, token_program, &taker.pubkey(), 50, None).await;
+
+    // 1 SOL for 50 tokens, the SOL sits in the escrow pda itself
+    let sol_for_tokens = Legs { mint_a: None, mint_b: Some(mint), token_program_a: token_program, token_program_b: token_program };
+    let escrow = escrow_pda(&maker.pubkey(), 0);
+    send(&mut context, &[make_ix(&maker.pubkey(), 0, sol_for_tokens, LAMPORTS_PER_SOL, 50, 0, None)], &[&maker]).await.unwrap();
+
+    let taker_before = balance(&mut context, taker.pubkey()).await;
+    let escrow_lamports = balance(&mut context, escrow).await; // deposit plus rent, all of it goes to the taker
+    let ata_rent = context.banks_client.get_rent().await.unwrap().minimum_balance(TokenAccount::LEN);
+
+    // paying in SOL for the token leg doesn't match the escrow
+    let wrong_legs = Legs { mint_b: None, ..sol_for_tokens };
+    let result = send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, wrong_legs, None)], &[&taker]).await;
+    assert_custom_error(result, EscrowError::WrongMint.into());
+
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 0, sol_for_tokens, None)], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &mint, &token_program)).await, Some(50));
+    // the taker also paid for the maker's ata
+    assert_eq!(balance(&mut context, taker.pubkey()).await, taker_before + escrow_lamports - ata_rent);
+    assert!(context.banks_client.get_account(escrow).await.unwrap().is_none());
+
+    // and back: the maker offers the 50 tokens for 2 SOL
+    let tokens_for_sol = Legs { mint_a: Some(mint), mint_b: None, token_program_a: token_program, token_program_b: token_program };
+    send(&mut context, &[make_ix(&maker.pubkey(), 1, tokens_for_sol, 50, 2 * LAMPORTS_PER_SOL, 0, None)], &[&maker]).await.unwrap();
+
+    let maker_before = balance(&mut context, maker.pubkey()).await;
+    send(&mut context, &[take_ix(&taker.pubkey(), &maker.pubkey(), 1, tokens_for_sol, None)], &[&taker]).await.unwrap();
+    assert_eq!(balance(&mut context, maker.pubkey()).await, maker_before + 2 * LAMPORTS_PER_SOL);
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &mint, &token_program)).await, Some(50));
+}
+
+#[tokio::test]
+async fn basket_rejects_wrong_leg_accounts() {
+    let (mut context, maker, taker) = setup().await;
+    let token_program = anchor_spl::token::ID;
+    let a1 = create_mint(&mut context, token_program, &maker.pubkey(), 100, None).await;
+    let a2 = create_mint(&mut context, token_program, &maker.pubkey(), 100, None).await;
+    let b1 = create_mint(&mut context, token_program, &taker.pubkey(), 100, None).await;
+    let offered = vec![BasketLeg { mint: a1, amount: 10 }, BasketLeg { mint: a2, amount: 20 }];
+    let requested = vec![BasketLeg { mint: b1, amount: 30 }];
+    let basket = basket_pda(&maker.pubkey(), 0);
+
+    // the accounts have to follow the legs' order
+    let result = send(&mut context, &[make_basket_ix(&maker.pubkey(), 0, offered.clone(), requested.clone(), &[a2, a1])], &[&maker]).await;
+    assert_custom_error(result, EscrowError::WrongLegAccounts.into());
+    send(&mut context, &[make_basket_ix(&maker.pubkey(), 0, offered, requested, &[a1, a2])], &[&maker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&basket, &a2, &token_program)).await, Some(20));
+
+    // misordered offered legs, and one of them left out
+    let result = send(&mut context, &[take_basket_ix(&taker.pubkey(), &maker.pubkey(), 0, &[b1], &[a2, a1])], &[&taker]).await;
+    assert_custom_error(result, EscrowError::WrongLegAccounts.into());
+    let result = send(&mut context, &[take_basket_ix(&taker.pubkey(), &maker.pubkey(), 0, &[b1], &[a1])], &[&taker]).await;
+    assert_custom_error(result, EscrowError::WrongLegAccounts.into());
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &b1, &token_program)).await, Some(100));
+
+    // the maker's own token account in place of the basket's vault
+    let maker_ata = ata(&maker.pubkey(), &a1, &token_program);
+    let result = send(&mut context, &[refund_basket_ix(&maker.pubkey(), 0, &[a1, a2], &[maker_ata, ata(&basket, &a2, &token_program)])], &[&maker]).await;
+    assert_custom_error(result, EscrowError::WrongLegAccounts.into());
+
+    send(&mut context, &[take_basket_ix(&taker.pubkey(), &maker.pubkey(), 0, &[b1], &[a1, a2])], &[&taker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &a1, &token_program)).await, Some(10));
+    assert_eq!(token_balance(&mut context, ata(&taker.pubkey(), &a2, &token_program)).await, Some(20));
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &b1, &token_program)).await, Some(30));
+    assert_eq!(token_balance(&mut context, ata(&basket, &a1, &token_program)).await, None);
+    assert!(context.banks_client.get_account(basket).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn basket_refund_returns_every_leg() {
+    let (mut context, maker, _) = setup().await;
+    let token_program = anchor_spl::token::ID;
+    let a1 = create_mint(&mut context, token_program, &maker.pubkey(), 100, None).await;
+    let a2 = create_mint(&mut context, token_program, &maker.pubkey(), 100, None).await;
+    let offered = vec![BasketLeg { mint: a1, amount: 10 }, BasketLeg { mint: a2, amount: 20 }];
+    let requested = vec![BasketLeg { mint: Pubkey::new_unique(), amount: 1 }];
+    let basket = basket_pda(&maker.pubkey(), 0);
+    let vaults = [ata(&basket, &a1, &token_program), ata(&basket, &a2, &token_program)];
+
+    send(&mut context, &[make_basket_ix(&maker.pubkey(), 0, offered, requested, &[a1, a2])], &[&maker]).await.unwrap();
+
+    // leaving a leg out would strand its vault
+    let result = send(&mut context, &[refund_basket_ix(&maker.pubkey(), 0, &[a1], &vaults[..1])], &[&maker]).await;
+    assert_custom_error(result, EscrowError::WrongLegAccounts.into());
+
+    send(&mut context, &[refund_basket_ix(&maker.pubkey(), 0, &[a1, a2], &vaults)], &[&maker]).await.unwrap();
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &a1, &token_program)).await, Some(100));
+    assert_eq!(token_balance(&mut context, ata(&maker.pubkey(), &a2, &token_program)).await, Some(100));
+    assert_eq!(token_balance(&mut context, vaults[1]).await, None);
+    assert!(context.banks_client.get_account(basket).await.unwrap().is_none());
+}
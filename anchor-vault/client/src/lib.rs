@@ -0,0 +1,130 @@
+// off-chain helpers for anchor_vault, so a backend or a test doesn't have to
+// re-derive the pdas and hand build the account lists every time
+use anchor_lang::{
+    prelude::*,
+    solana_program::instruction::Instruction,
+    system_program,
+    AccountDeserialize,
+    Discriminator,
+    InstructionData
+};
+use anchor_vault::state::{VaultState, CreatorIndex};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::RpcProgramAccountsConfig,
+    rpc_filter::{Memcmp, RpcFilterType}
+};
+
+pub use anchor_vault::ID;
+
+// the state is keyed by whoever created it, which stays the same after an ownership transfer
+pub fn state_address(creator: &Pubkey, vault_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"state", creator.as_ref(), &vault_id.to_le_bytes()], &ID).0
+}
+
+// lists the vault ids a wallet created, not the ones it owns now (see fetch_owned_states)
+pub fn creator_index_address(creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"creator_index", creator.as_ref()], &ID).0
+}
+
+pub fn vault_address(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", state.as_ref()], &ID).0
+}
+
+pub fn recovery_address(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"recovery", state.as_ref()], &ID).0
+}
+
+pub fn initialize(owner: &Pubkey, vault_id: u64, unlock_ts: i64) -> Instruction {
+    let state = state_address(owner, vault_id);
+    Instruction {
+        program_id: ID,
+        accounts: anchor_vault::accounts::Initialize {
+            owner: *owner,
+            state,
+            creator_index: creator_index_address(owner),
+            vault: vault_address(&state),
+            system_program: system_program::ID
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::Initialize { vault_id, unlock_ts }.data()
+    }
+}
+
+// deposit and withdraw share the same accounts
+fn payment_accounts(owner: &Pubkey, state: &Pubkey) -> Vec<AccountMeta> {
+    anchor_vault::accounts::Payment {
+        owner: *owner,
+        state: *state,
+        vault: vault_address(state),
+        system_program: system_program::ID
+    }.to_account_metas(None)
+}
+
+pub fn deposit(owner: &Pubkey, state: &Pubkey, lamports: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: payment_accounts(owner, state),
+        data: anchor_vault::instruction::Deposit { lamports }.data()
+    }
+}
+
+pub fn withdraw(owner: &Pubkey, state: &Pubkey, lamports: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: payment_accounts(owner, state),
+        data: anchor_vault::instruction::Withdraw { lamports }.data()
+    }
+}
+
+// needs the creator for the creator index, after an ownership transfer that's not the owner anymore
+pub fn close(owner: &Pubkey, creator: &Pubkey, state: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: anchor_vault::accounts::Close {
+            owner: *owner,
+            state: *state,
+            creator_index: creator_index_address(creator),
+            vault: vault_address(state),
+            recovery: recovery_address(state),
+            system_program: system_program::ID
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::Close {}.data()
+    }
+}
+
+// checks the discriminator, so handing it some other account's data fails instead of returning garbage
+// works on data from anywhere: an rpc, a banks client in tests, a websocket subscription
+pub fn decode_state(mut data: &[u8]) -> Result<VaultState> {
+    VaultState::try_deserialize(&mut data)
+}
+
+pub fn decode_creator_index(mut data: &[u8]) -> Result<CreatorIndex> {
+    CreatorIndex::try_deserialize(&mut data)
+}
+
+pub fn fetch_state(rpc: &RpcClient, state: &Pubkey) -> std::result::Result<VaultState, Box<dyn std::error::Error>> {
+    let account = rpc.get_account(state)?;
+    if account.owner != ID {
+        // someone else's account could be crafted to carry our discriminator
+        return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram).into());
+    }
+    Ok(decode_state(&account.data)?)
+}
+
+// every vault the wallet owns right now, including ones it was transferred or recovered into
+// owner sits after the discriminator, creator and vault_id
+pub fn fetch_owned_states(rpc: &RpcClient, owner: &Pubkey) -> std::result::Result<Vec<(Pubkey, VaultState)>, Box<dyn std::error::Error>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, VaultState::DISCRIMINATOR.as_ref())),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8 + 32 + 8, owner.as_ref()))
+        ]),
+        ..Default::default()
+    };
+
+    let mut states = Vec::new();
+    for (address, account) in rpc.get_program_accounts_with_config(&ID, config)? {
+        states.push((address, decode_state(&account.data)?));
+    }
+    Ok(states)
+}
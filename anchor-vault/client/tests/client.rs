@@ -0,0 +1,52 @@
+use anchor_lang::{prelude::*, solana_program::entrypoint::ProgramResult};
+use anchor_vault_client as client;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+
+// same wrapper as the program's own tests, program-test's account infos don't live long enough for anchor
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_vault::entry(program_id, accounts, data)
+}
+
+#[tokio::test]
+async fn builds_instructions_and_decodes_state() {
+    let program_test = ProgramTest::new("anchor_vault", client::ID, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+
+    let owner = Keypair::new();
+    let state = client::state_address(&owner.pubkey(), 7);
+    let vault = client::vault_address(&state);
+
+    let instructions = [
+        system_instruction::transfer(&context.payer.pubkey(), &owner.pubkey(), 2 * LAMPORTS_PER_SOL),
+        client::initialize(&owner.pubkey(), 7, 0),
+        client::deposit(&owner.pubkey(), &state, LAMPORTS_PER_SOL),
+        client::withdraw(&owner.pubkey(), &state, LAMPORTS_PER_SOL / 4),
+    ];
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&context.payer.pubkey()), &[&context.payer, &owner], context.last_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    assert_eq!(context.banks_client.get_balance(vault).await.unwrap(), 3 * LAMPORTS_PER_SOL / 4);
+
+    let account = context.banks_client.get_account(state).await.unwrap().unwrap();
+    let decoded = client::decode_state(&account.data).unwrap();
+    assert_eq!(decoded.owner, owner.pubkey());
+    assert_eq!(decoded.creator, owner.pubkey());
+    assert_eq!(decoded.vault_id, 7);
+
+    let account = context.banks_client.get_account(client::creator_index_address(&owner.pubkey())).await.unwrap().unwrap();
+    assert_eq!(client::decode_creator_index(&account.data).unwrap().vault_ids, vec![7]);
+
+    // anything without the VaultState discriminator is rejected
+    assert!(client::decode_state(&[0; 8]).is_err());
+
+    let tx = Transaction::new_signed_with_payer(&[client::close(&owner.pubkey(), &owner.pubkey(), &state)], Some(&context.payer.pubkey()), &[&context.payer, &owner], context.last_blockhash);
+    context.banks_client.process_transaction(tx).await.unwrap();
+    assert!(context.banks_client.get_account(state).await.unwrap().is_none());
+}
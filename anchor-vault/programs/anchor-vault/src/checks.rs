@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+// a system account has to stay rent exempt unless it's drained to 0, in which case it's garbage collected
+fn check_rent_exempt(remaining: u64) -> Result<()> {
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        VaultError::WouldBreakRentExemption
+    );
+    Ok(())
+}
+
+pub fn check_deposit(from_lamports: u64, vault_lamports: u64, amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroAmount);
+    require!(from_lamports >= amount, VaultError::InsufficientFunds);
+    check_rent_exempt(vault_lamports.checked_add(amount).ok_or(VaultError::Overflow)?)
+}
+
+pub fn check_withdraw(vault_lamports: u64, amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroAmount);
+    require!(vault_lamports >= amount, VaultError::InsufficientFunds);
+    check_rent_exempt(vault_lamports - amount)
+}
+
+pub fn check_token_withdraw(vault_amount: u64, amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroAmount);
+    require!(vault_amount >= amount, VaultError::InsufficientFunds);
+    Ok(())
+}
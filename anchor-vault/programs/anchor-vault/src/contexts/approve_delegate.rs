@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::{state::{VaultState, Delegation}, error::VaultError};
+
+#[derive(Accounts)]
+pub struct ApproveDelegate <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub delegate: SystemAccount<'info>,
+
+    #[account(
+        mut, // counts the delegation as open
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed, // so the owner can update an allowance without revoking first
+        payer = owner,
+        space = Delegation::INIT_SPACE,
+        seeds = [b"delegate", state.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> ApproveDelegate<'info> {
+    pub fn approve_delegate(&mut self, allowance: u64, period: i64, bumps: &ApproveDelegateBumps) -> Result<()> {
+        require!(period >= 0, VaultError::InvalidPeriod);
+
+        // only a new delegation is counted, approving an existing one again just updates it
+        if self.delegation.state == Pubkey::default() {
+            self.state.open_account()?;
+        }
+
+        self.delegation.set_inner(Delegation {
+            state: self.state.key(),
+            delegate: self.delegate.key(),
+            allowance,
+            period,
+            period_start: Clock::get()?.unix_timestamp,
+            spent: 0,
+            bump: bumps.delegation
+        });
+
+        Ok(())
+    }
+}
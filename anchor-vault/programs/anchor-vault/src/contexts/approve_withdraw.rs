@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::{state::{MultisigVault, WithdrawProposal}, error::VaultError};
+
+#[derive(Accounts)]
+pub struct ApproveWithdraw <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"multisig", multisig.creator.as_ref(), multisig.seed.to_le_bytes().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, MultisigVault>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = multisig
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+}
+
+impl<'info> ApproveWithdraw<'info> {
+    pub fn approve_withdraw(&mut self) -> Result<()> {
+        let owner_index = self.multisig.owner_index(&self.owner.key())?;
+        require!(!self.proposal.approvals[owner_index], VaultError::AlreadyApproved);
+
+        self.proposal.approvals[owner_index] = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::VaultState, error::VaultError, checks::check_withdraw, events::PayoutEvent};
+
+#[derive(Accounts)]
+pub struct BatchWithdraw <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> BatchWithdraw<'info> {
+    pub fn batch_withdraw(&mut self, recipients: &[AccountInfo<'info>], amounts: Vec<u64>) -> Result<()> {
+        require!(!amounts.is_empty(), VaultError::EmptyBatch);
+        require!(recipients.len() == amounts.len(), VaultError::BatchLengthMismatch);
+
+        // check the whole batch up front so it either all goes through or nothing does
+        let mut total: u64 = 0;
+        for (recipient, amount) in recipients.iter().zip(amounts.iter()) {
+            require!(*amount > 0, VaultError::ZeroAmount);
+            require!(recipient.is_writable, VaultError::RecipientNotWritable);
+            total = total.checked_add(*amount).ok_or(VaultError::Overflow)?;
+        }
+
+        self.state.check_withdrawable()?;
+        check_withdraw(self.vault.lamports(), total)?;
+        self.state.record_withdraw(total, Clock::get()?.unix_timestamp)?;
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                state_key.as_ref(),
+                &[self.state.vault_bump]
+            ]
+        ];
+
+        for (recipient, amount) in recipients.iter().zip(amounts) {
+            let accounts = Transfer {
+                from: self.vault.to_account_info(),
+                to: recipient.clone(),
+            };
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                accounts,
+                &signer_seeds
+            );
+
+            transfer(transfer_ctx, amount)?;
+
+            emit!(PayoutEvent {
+                owner: self.owner.key(),
+                vault: self.vault.key(),
+                recipient: recipient.key(),
+                amount,
+                balance: self.vault.lamports(),
+                slot: Clock::get()?.slot
+            });
+        }
+
+        Ok(())
+    }
+}
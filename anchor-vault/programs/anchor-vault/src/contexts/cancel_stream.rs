@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{VaultState, Stream}, checks::check_withdraw, events::PayoutEvent};
+
+#[derive(Accounts)]
+pub struct CancelStream <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut, // closed in the handler, it stays open if the vault can't pay what's owed yet
+        seeds = [b"stream", state.key().as_ref(), recipient.key().as_ref(), stream.seed.to_le_bytes().as_ref()],
+        bump = stream.bump,
+        has_one = state,
+        has_one = recipient
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> CancelStream<'info> {
+    pub fn cancel_stream(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let amount = self.stream.accrued_amount(now)?;
+        if amount == 0 {
+            return self.close_stream();
+        }
+
+        // the recipient earned it, so settle before closing
+        let payable = self.state.check_withdrawable().is_ok()
+            && check_withdraw(self.vault.lamports(), amount).is_ok()
+            && self.state.record_withdraw(amount, now).is_ok();
+
+        if !payable {
+            // frozen, locked, over the cap or short of funds: stop the stream here so nothing more accrues,
+            // the recipient can collect what's owed with withdraw_stream later and the owner cancels again to close it
+            self.stream.end_ts = self.stream.end_ts.min(now);
+            return Ok(());
+        }
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.recipient.to_account_info(),
+        };
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                state_key.as_ref(),
+                &[self.state.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, amount)?;
+
+        emit!(PayoutEvent {
+            owner: self.owner.key(),
+            vault: self.vault.key(),
+            recipient: self.recipient.key(),
+            amount,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        self.close_stream()
+    }
+
+    fn close_stream(&mut self) -> Result<()> {
+        self.state.close_account();
+        self.stream.close(self.owner.to_account_info())
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{MultisigVault, WithdrawProposal};
+
+// a proposal that shouldn't go through anymore would otherwise sit there forever, ready for the last approval
+#[derive(Accounts)]
+pub struct CancelWithdraw <'info>{
+    #[account(mut)]
+    pub proposer: Signer<'info>, // only whoever proposed it, they paid the rent
+
+    #[account(
+        seeds = [b"multisig", multisig.creator.as_ref(), multisig.seed.to_le_bytes().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, MultisigVault>,
+
+    #[account(
+        mut,
+        close = proposer, // closing it is the cancel, a closed proposal can't be approved or executed
+        seeds = [b"proposal", multisig.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+}
+
+impl<'info> CancelWithdraw<'info> {
+    pub fn cancel_withdraw(&mut self) -> Result<()> {
+        // nothing to do besides the close, has_one = proposer already made sure it's theirs to cancel
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::Vesting, error::VaultError, events::WithdrawEvent};
+
+#[derive(Accounts)]
+pub struct Claim <'info>{
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(mut)]
+    pub funder: SystemAccount<'info>, // gets the rent back once everything is claimed
+
+    #[account(
+        mut,
+        seeds = [b"vesting", funder.key().as_ref(), beneficiary.key().as_ref(), vesting.seed.to_le_bytes().as_ref()],
+        bump = vesting.bump,
+        has_one = funder,
+        has_one = beneficiary,
+        constraint = vesting.mint.is_none() @ VaultError::WrongVestingAsset
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vesting.key().as_ref()],
+        bump = vesting.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Claim<'info> {
+    pub fn claim(&mut self) -> Result<()> {
+        let amount = self.vesting.claimable_amount(Clock::get()?.unix_timestamp);
+        require!(amount > 0, VaultError::NothingToClaim);
+
+        self.vesting.claimed_amount += amount;
+        self.pay(self.beneficiary.to_account_info(), amount)?;
+
+        emit!(WithdrawEvent {
+            owner: self.vesting.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount,
+            balance: self.vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0)), // same as in create_vesting, rent isn't counted
+            slot: Clock::get()?.slot
+        });
+
+        if self.vesting.is_fully_claimed() {
+            // only the rent the funder put in is left, give it back and close the vesting account
+            self.pay(self.funder.to_account_info(), self.vault.lamports())?;
+            self.vesting.close(self.funder.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    fn pay(&self, to: AccountInfo<'info>, lamports: u64) -> Result<()> {
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to,
+        };
+
+        let vesting_key = self.vesting.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                vesting_key.as_ref(),
+                &[self.vesting.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, lamports)
+    }
+}
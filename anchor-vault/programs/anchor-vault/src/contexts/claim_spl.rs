@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account},
+    associated_token::AssociatedToken
+};
+use crate::{state::Vesting, error::VaultError, events::WithdrawEvent, transfer::{transfer_checked, harvest_withheld}};
+
+#[derive(Accounts)]
+pub struct ClaimSpl <'info>{
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(mut)]
+    pub funder: SystemAccount<'info>,
+
+    #[account(mut)] // harvesting withheld transfer fees writes to the mint
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", funder.key().as_ref(), beneficiary.key().as_ref(), vesting.seed.to_le_bytes().as_ref()],
+        bump = vesting.bump,
+        has_one = funder,
+        has_one = beneficiary,
+        constraint = vesting.mint == Some(mint.key()) @ VaultError::WrongVestingAsset
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vesting.key().as_ref(), mint.key().as_ref()],
+        bump = vesting.vault_bump,
+        token::mint = mint,
+        token::authority = vesting,
+        token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> ClaimSpl<'info> {
+    pub fn claim(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let amount = self.vesting.claimable_amount(Clock::get()?.unix_timestamp);
+        require!(amount > 0, VaultError::NothingToClaim);
+
+        self.vesting.claimed_amount += amount;
+
+        // the last claim sweeps the vault so close_account can't be blocked by someone sending it extra tokens
+        let amount = if self.vesting.is_fully_claimed() { self.vault_ata.amount } else { amount };
+
+        let seed = self.vesting.seed.to_le_bytes();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vesting",
+                self.funder.to_account_info().key.as_ref(),
+                self.beneficiary.to_account_info().key.as_ref(),
+                &seed[..],
+                &[self.vesting.bump]
+            ]
+        ];
+
+        let accounts = TransferChecked {
+            from: self.vault_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.beneficiary_ata.to_account_info(),
+            authority: self.vesting.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        ).with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        emit!(WithdrawEvent {
+            owner: self.vesting.key(),
+            vault: self.vault_ata.key(),
+            mint: Some(self.mint.key()),
+            amount,
+            balance: self.vault_ata.amount - amount,
+            slot: Clock::get()?.slot
+        });
+
+        if self.vesting.is_fully_claimed() {
+            // the vault is empty now, the funder paid for it so they get the rent back
+            harvest_withheld(
+                self.token_program.to_account_info(),
+                self.mint.to_account_info(),
+                self.vault_ata.to_account_info()
+            )?;
+
+            let close_accounts = CloseAccount {
+                account: self.vault_ata.to_account_info(),
+                destination: self.funder.to_account_info(),
+                authority: self.vesting.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                close_accounts,
+                &signer_seeds
+            );
+
+            close_account(cpi_ctx)?;
+            self.vesting.close(self.funder.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
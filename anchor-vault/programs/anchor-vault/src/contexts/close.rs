@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{VaultState, CreatorIndex, Recovery}, error::VaultError, events::WithdrawEvent};
+
+#[derive(Accounts)]
+pub struct Close <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner, // the rent of the state goes back to the owner
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner,
+        constraint = state.staked_lamports == 0 @ VaultError::AlreadyStaked, // withdraw the stake first or it's stranded
+        constraint = state.open_accounts == 0 @ VaultError::OpenAccounts
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_index", state.creator.as_ref()],
+        bump = creator_index.bump
+    )]
+    pub creator_index: Account<'info, CreatorIndex>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", state.key().as_ref()],
+        bump
+    )]
+    /// CHECK: the seeds make sure it's this vault's recovery account, it may not exist
+    pub recovery: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Close<'info> {
+    pub fn close(&mut self) -> Result<()> {
+        self.state.check_withdrawable()?; // closing drains the vault so it has to respect the lock and freeze too
+
+        self.creator_index.remove(self.state.vault_id); // the id can be reused once the state is gone
+        // the recovery keys would otherwise carry over to a vault initialized again with the same id
+        Recovery::close_if_set(&self.recovery.to_account_info(), &self.owner.to_account_info())?;
+
+        // a system account with 0 lamports gets garbage collected, so draining it is enough to close it
+        let lamports = self.vault.lamports();
+        if lamports == 0 {
+            return Ok(());
+        }
+        self.state.record_withdraw(lamports, Clock::get()?.unix_timestamp)?;
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.owner.to_account_info(),
+        };
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                state_key.as_ref(),
+                &[self.state.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, lamports)?;
+
+        emit!(WithdrawEvent {
+            owner: self.owner.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: 0,
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::{state::{MultisigVault, MAX_OWNERS}, error::VaultError};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateMultisig <'info>{
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = MultisigVault::INIT_SPACE,
+        seeds = [b"multisig", creator.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, MultisigVault>,
+
+    #[account(
+        seeds = [b"vault", multisig.key().as_ref()], // anyone can fund it with a plain transfer
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> CreateMultisig<'info> {
+    pub fn create_multisig(&mut self, seed: u64, owners: Vec<Pubkey>, threshold: u8, bumps: &CreateMultisigBumps) -> Result<()> {
+        require!(owners.len() <= MAX_OWNERS, VaultError::TooManyOwners);
+        require!(threshold > 0 && threshold as usize <= owners.len(), VaultError::InvalidThreshold);
+        for (i, owner) in owners.iter().enumerate() {
+            require!(!owners[..i].contains(owner), VaultError::DuplicateOwner);
+        }
+
+        self.multisig.set_inner(MultisigVault {
+            creator: self.creator.key(),
+            seed,
+            owners,
+            threshold,
+            proposal_count: 0,
+            bump: bumps.multisig,
+            vault_bump: bumps.vault
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::Pool;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreatePool <'info>{
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = Pool::INIT_SPACE,
+        seeds = [b"pool", creator.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [b"vault", pool.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> CreatePool<'info> {
+    pub fn create_pool(&mut self, seed: u64, bumps: &CreatePoolBumps) -> Result<()> {
+        self.pool.set_inner(Pool {
+            creator: self.creator.key(),
+            seed,
+            total_shares: 0,
+            bump: bumps.pool,
+            vault_bump: bumps.vault
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::{state::{VaultState, Stream}, error::VaultError};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateStream <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut, // counts the stream as open
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Stream::INIT_SPACE,
+        seeds = [b"stream", state.key().as_ref(), recipient.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> CreateStream<'info> {
+    pub fn create_stream(&mut self, seed: u64, rate: u64, start_ts: i64, end_ts: i64, bumps: &CreateStreamBumps) -> Result<()> {
+        require!(rate > 0 && start_ts < end_ts, VaultError::InvalidStream);
+
+        // the full stream has to fit in a u64, or accrued_amount would fail forever and the stream could never be settled
+        let duration = end_ts.checked_sub(start_ts).ok_or(VaultError::Overflow)?;
+        rate.checked_mul(duration as u64).ok_or(VaultError::Overflow)?;
+
+        self.state.open_account()?;
+
+        self.stream.set_inner(Stream {
+            state: self.state.key(),
+            recipient: self.recipient.key(),
+            seed,
+            rate,
+            start_ts,
+            end_ts,
+            withdrawn: 0,
+            bump: bumps.stream
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::Vesting, events::DepositEvent};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateVesting <'info>{
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub beneficiary: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = funder,
+        space = Vesting::INIT_SPACE,
+        seeds = [b"vesting", funder.key().as_ref(), beneficiary.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vesting.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> CreateVesting<'info> {
+    pub fn save(
+        &mut self,
+        seed: u64,
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+        bumps: &CreateVestingBumps
+    ) -> Result<()> {
+        self.vesting.set_inner(Vesting {
+            funder: self.funder.key(),
+            beneficiary: self.beneficiary.key(),
+            mint: None,
+            seed,
+            total_amount: amount,
+            claimed_amount: 0,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            bump: bumps.vesting,
+            vault_bump: bumps.vault
+        });
+
+        self.vesting.check_schedule()
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        // the funder also covers the rent of the vault so partial claims never leave it below the rent exempt minimum
+        let rent = Rent::get()?.minimum_balance(0);
+
+        let accounts = Transfer {
+            from: self.funder.to_account_info(),
+            to: self.vault.to_account_info(),
+        };
+
+        let transfer_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+        transfer(transfer_ctx, amount + rent)?;
+
+        emit!(DepositEvent {
+            owner: self.vesting.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount, // the rent top up isn't part of the schedule, so the indexer shouldn't see it either
+            balance: self.vault.lamports() - rent,
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked}, associated_token::AssociatedToken};
+use crate::{state::Vesting, error::VaultError, events::DepositEvent, transfer::{transfer_checked, net_amount}};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateVestingSpl <'info>{
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub beneficiary: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = funder,
+        associated_token::token_program = token_program
+    )]
+    pub funder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = funder,
+        space = Vesting::INIT_SPACE,
+        seeds = [b"vesting", funder.key().as_ref(), beneficiary.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        init,
+        payer = funder,
+        seeds = [b"vault", vesting.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting, // the vesting pda signs for the claims
+        token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> CreateVestingSpl<'info> {
+    pub fn save(
+        &mut self,
+        seed: u64,
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+        bumps: &CreateVestingSplBumps
+    ) -> Result<()> {
+        // with a transfer fee the vault ends up with less than the funder sent, vest what actually arrives
+        let amount = net_amount(&self.mint.to_account_info(), amount)?;
+        require!(amount > 0, VaultError::ZeroAmount);
+
+        self.vesting.set_inner(Vesting {
+            funder: self.funder.key(),
+            beneficiary: self.beneficiary.key(),
+            mint: Some(self.mint.key()),
+            seed,
+            total_amount: amount,
+            claimed_amount: 0,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            bump: bumps.vesting,
+            vault_bump: bumps.vault_ata
+        });
+
+        self.vesting.check_schedule()
+    }
+
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>], amount: u64) -> Result<()> {
+        let accounts = TransferChecked {
+            from: self.funder_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault_ata.to_account_info(),
+            authority: self.funder.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        emit!(DepositEvent {
+            owner: self.vesting.key(),
+            vault: self.vault_ata.key(),
+            mint: Some(self.mint.key()),
+            amount: self.vesting.total_amount, // net of any transfer fee, see save
+            balance: self.vesting.total_amount, // the vault was just created
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
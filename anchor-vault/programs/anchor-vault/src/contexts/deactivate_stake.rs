@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, stake::{self, instruction as stake_instruction}};
+use crate::{state::VaultState, error::VaultError};
+
+#[derive(Accounts)]
+pub struct DeactivateStake <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner,
+        constraint = state.staked_lamports > 0 @ VaultError::NotStaked
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", state.key().as_ref()],
+        bump
+    )]
+    /// CHECK: the seeds make sure it's the vault's stake account
+    pub stake_account: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    #[account(address = stake::program::ID)]
+    /// CHECK: address is checked
+    pub stake_program: UncheckedAccount<'info>
+}
+
+impl<'info> DeactivateStake<'info> {
+    pub fn deactivate_stake(&mut self) -> Result<()> {
+        let ix = stake_instruction::deactivate_stake(&self.stake_account.key(), &self.vault.key());
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[b"vault", state_key.as_ref(), &[self.state.vault_bump]]
+        ];
+
+        invoke_signed(
+            &ix,
+            &[
+                self.stake_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.vault.to_account_info(),
+                self.stake_program.to_account_info()
+            ],
+            &signer_seeds
+        ).map_err(Into::into)
+    }
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{CreateAccount, create_account, Transfer, transfer, Allocate, allocate, Assign, assign};
+use anchor_lang::solana_program::{
+    program::invoke_signed,
+    stake::{self, instruction as stake_instruction, state::{Authorized, Lockup, StakeStateV2}},
+    sysvar
+};
+use crate::{state::VaultState, error::VaultError, checks::check_withdraw, events::WithdrawEvent};
+
+#[derive(Accounts)]
+pub struct DelegateStake <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner,
+        constraint = state.staked_lamports == 0 @ VaultError::AlreadyStaked
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>, // pays for the stake account and is its staker and withdrawer
+
+    #[account(
+        mut,
+        seeds = [b"stake", state.key().as_ref()],
+        bump
+    )]
+    /// CHECK: created here as a stake account, the seeds make sure it's the vault's
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: the stake program checks it's a vote account when delegating
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// CHECK: the stake program checks the address
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(address = sysvar::stake_history::ID)]
+    /// CHECK: address is checked
+    pub stake_history: UncheckedAccount<'info>,
+    #[account(address = stake::program::ID)]
+    /// CHECK: address is checked
+    pub stake_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> DelegateStake<'info> {
+    pub fn create_stake_account(&mut self, lamports: u64, bumps: &DelegateStakeBumps) -> Result<()> {
+        check_withdraw(self.vault.lamports(), lamports)?;
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 2] = [
+            &[b"vault", state_key.as_ref(), &[self.state.vault_bump]],
+            &[b"stake", state_key.as_ref(), &[bumps.stake_account]] // the new account has to sign its own creation
+        ];
+
+        if self.stake_account.lamports() == 0 {
+            let accounts = CreateAccount {
+                from: self.vault.to_account_info(),
+                to: self.stake_account.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(self.system_program.to_account_info(), accounts, &signer_seeds);
+
+            create_account(cpi_ctx, lamports, StakeStateV2::size_of() as u64, &stake::program::ID)?;
+        } else {
+            // anyone can send lamports to the stake address and create_account refuses an account that has some,
+            // so do what anchor's init does and fund, allocate and assign it in separate steps
+            let accounts = Transfer {
+                from: self.vault.to_account_info(),
+                to: self.stake_account.to_account_info()
+            };
+            transfer(CpiContext::new_with_signer(self.system_program.to_account_info(), accounts, &signer_seeds), lamports)?;
+
+            let accounts = Allocate { account_to_allocate: self.stake_account.to_account_info() };
+            allocate(CpiContext::new_with_signer(self.system_program.to_account_info(), accounts, &signer_seeds), StakeStateV2::size_of() as u64)?;
+
+            let accounts = Assign { account_to_assign: self.stake_account.to_account_info() };
+            assign(CpiContext::new_with_signer(self.system_program.to_account_info(), accounts, &signer_seeds), &stake::program::ID)?;
+        }
+
+        // the vault pda is both staker and withdrawer so only this program can move the stake
+        let ix = stake_instruction::initialize(
+            &self.stake_account.key(),
+            &Authorized { staker: self.vault.key(), withdrawer: self.vault.key() },
+            &Lockup::default()
+        );
+
+        invoke_signed(
+            &ix,
+            &[self.stake_account.to_account_info(), self.rent.to_account_info(), self.stake_program.to_account_info()],
+            &signer_seeds
+        )?;
+
+        self.state.staked_lamports = self.stake_account.lamports(); // includes anything that was sent to it before
+
+        emit!(WithdrawEvent {
+            owner: self.owner.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+
+    pub fn delegate_stake(&mut self) -> Result<()> {
+        let ix = stake_instruction::delegate_stake(&self.stake_account.key(), &self.vault.key(), &self.vote_account.key());
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[b"vault", state_key.as_ref(), &[self.state.vault_bump]]
+        ];
+
+        invoke_signed(
+            &ix,
+            &[
+                self.stake_account.to_account_info(),
+                self.vote_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.vault.to_account_info(),
+                self.stake_program.to_account_info()
+            ],
+            &signer_seeds
+        ).map_err(Into::into)
+    }
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{VaultState, Delegation}, checks::check_withdraw, events::WithdrawEvent};
+
+#[derive(Accounts)]
+pub struct DelegateWithdraw <'info>{
+    #[account(mut)]
+    pub delegate: Signer<'info>, // the lamports go to the delegate
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"delegate", state.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.bump,
+        has_one = state,
+        has_one = delegate
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> DelegateWithdraw<'info> {
+    pub fn delegate_withdraw(&mut self, lamports: u64) -> Result<()> {
+        self.state.check_withdrawable()?;
+        check_withdraw(self.vault.lamports(), lamports)?;
+        self.state.record_withdraw(lamports, Clock::get()?.unix_timestamp)?;
+        self.delegation.spend(lamports, Clock::get()?.unix_timestamp)?;
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.delegate.to_account_info(),
+        };
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                state_key.as_ref(),
+                &[self.state.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, lamports)?;
+
+        emit!(WithdrawEvent {
+            owner: self.state.owner,
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
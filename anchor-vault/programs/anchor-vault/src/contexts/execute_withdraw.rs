@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{MultisigVault, WithdrawProposal}, error::VaultError, checks::check_withdraw, events::WithdrawEvent};
+
+#[derive(Accounts)]
+pub struct ExecuteWithdraw <'info>{
+    pub executor: Signer<'info>, // anyone can execute once there are enough approvals
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"multisig", multisig.creator.as_ref(), multisig.seed.to_le_bytes().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, MultisigVault>,
+
+    #[account(
+        mut,
+        close = proposer, // a proposal can only be executed once
+        seeds = [b"proposal", multisig.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        has_one = recipient
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", multisig.key().as_ref()],
+        bump = multisig.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> ExecuteWithdraw<'info> {
+    pub fn execute_withdraw(&mut self) -> Result<()> {
+        require!(
+            self.proposal.approval_count() >= self.multisig.threshold as usize,
+            VaultError::NotEnoughApprovals
+        );
+        check_withdraw(self.vault.lamports(), self.proposal.amount)?;
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.recipient.to_account_info(),
+        };
+
+        let multisig_key = self.multisig.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                multisig_key.as_ref(),
+                &[self.multisig.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, self.proposal.amount)?;
+
+        emit!(WithdrawEvent {
+            owner: self.multisig.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: self.proposal.amount,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::{state::VaultState, error::VaultError};
+
+#[derive(Accounts)]
+pub struct ExtendLock <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+impl<'info> ExtendLock<'info> {
+    pub fn extend_lock(&mut self, unlock_ts: i64) -> Result<()> {
+        // never let the owner shorten the lock, otherwise it wouldn't be a lock
+        require!(unlock_ts >= self.state.unlock_ts, VaultError::LockShortened);
+        self.state.unlock_ts = unlock_ts;
+
+        Ok(())
+    }
+}
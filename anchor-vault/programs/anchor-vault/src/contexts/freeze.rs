@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use crate::{state::VaultState, error::VaultError};
+
+#[derive(Accounts)]
+pub struct Freeze <'info>{
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        constraint = state.guardian == Some(guardian.key()) @ VaultError::NotGuardian
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+impl<'info> Freeze<'info> {
+    pub fn set_frozen(&mut self, frozen: bool) -> Result<()> {
+        self.state.frozen = frozen;
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::state::{VaultState, CreatorIndex};
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct Initialize <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = VaultState::INIT_SPACE,
+        seeds = [b"state", owner.key().as_ref(), vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub state: Account<'info, VaultState>, // on-chain record of who owns the vault
+
+    #[account(
+        init_if_needed, // created with the first vault, shared by the rest
+        payer = owner,
+        space = CreatorIndex::INIT_SPACE,
+        seeds = [b"creator_index", owner.key().as_ref()],
+        bump
+    )]
+    pub creator_index: Account<'info, CreatorIndex>,
+
+    #[account(
+        seeds = [b"vault", state.key().as_ref()], // the vault inherits the owner through the state key
+        bump
+    )]
+    pub vault: SystemAccount<'info>, // nothing to init, it comes alive with the first deposit
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, vault_id: u64, unlock_ts: i64, bumps: &InitializeBumps) -> Result<()> {
+        if self.creator_index.creator == Pubkey::default() {
+            self.creator_index.creator = self.owner.key();
+            self.creator_index.bump = bumps.creator_index;
+        }
+        self.creator_index.add(vault_id)?;
+
+        self.state.set_inner(VaultState {
+            creator: self.owner.key(),
+            vault_id,
+            owner: self.owner.key(),
+            state_bump: bumps.state,
+            vault_bump: bumps.vault,
+            created_at: Clock::get()?.unix_timestamp,
+            unlock_ts,
+            staked_lamports: 0,
+            guardian: None,
+            frozen: false,
+            pending_guardian: None,
+            guardian_change_ts: 0,
+            withdraw_cap: 0,
+            window_start: 0,
+            spent_in_window: 0,
+            pending_cap: 0,
+            cap_change_ts: 0,
+            open_accounts: 0
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+pub mod initialize;
+pub use initialize::*;
+
+pub mod extend_lock;
+pub use extend_lock::*;
+
+pub mod payment;
+pub use payment::*;
+
+pub mod spl_payment;
+pub use spl_payment::*;
+
+pub mod nft_payment;
+pub use nft_payment::*;
+
+pub mod create_vesting;
+pub use create_vesting::*;
+
+pub mod create_vesting_spl;
+pub use create_vesting_spl::*;
+
+pub mod claim;
+pub use claim::*;
+
+pub mod claim_spl;
+pub use claim_spl::*;
+
+pub mod create_multisig;
+pub use create_multisig::*;
+
+pub mod propose_withdraw;
+pub use propose_withdraw::*;
+
+pub mod approve_withdraw;
+pub use approve_withdraw::*;
+
+pub mod execute_withdraw;
+pub use execute_withdraw::*;
+
+pub mod cancel_withdraw;
+pub use cancel_withdraw::*;
+
+pub mod approve_delegate;
+pub use approve_delegate::*;
+
+pub mod delegate_withdraw;
+pub use delegate_withdraw::*;
+
+pub mod revoke_delegate;
+pub use revoke_delegate::*;
+
+pub mod delegate_stake;
+pub use delegate_stake::*;
+
+pub mod deactivate_stake;
+pub use deactivate_stake::*;
+
+pub mod withdraw_stake;
+pub use withdraw_stake::*;
+
+pub mod create_pool;
+pub use create_pool::*;
+
+pub mod pool_deposit;
+pub use pool_deposit::*;
+
+pub mod pool_withdraw;
+pub use pool_withdraw::*;
+
+pub mod freeze;
+pub use freeze::*;
+
+pub mod set_guardian;
+pub use set_guardian::*;
+
+pub mod transfer_ownership;
+pub use transfer_ownership::*;
+
+pub mod set_recovery;
+pub use set_recovery::*;
+
+pub mod recover;
+pub use recover::*;
+
+pub mod set_withdraw_cap;
+pub use set_withdraw_cap::*;
+
+pub mod batch_withdraw;
+pub use batch_withdraw::*;
+
+pub mod create_stream;
+pub use create_stream::*;
+
+pub mod withdraw_stream;
+pub use withdraw_stream::*;
+
+pub mod cancel_stream;
+pub use cancel_stream::*;
+
+pub mod close;
+pub use close::*;
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account},
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount, MasterEditionAccount}
+};
+use crate::{state::VaultState, error::VaultError, events::{DepositEvent, WithdrawEvent}, transfer::{transfer_checked, harvest_withheld}};
+
+#[derive(Accounts)]
+pub struct NftPayment <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut, // harvesting withheld transfer fees before closing the vault writes to the mint
+        constraint = mint.supply == 1 @ VaultError::NotAnNft,
+        constraint = mint.decimals == 0 @ VaultError::NotAnNft
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut, // counts the token vault as open while it holds the nft
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"vault", state.key().as_ref(), mint.key().as_ref()], // same per mint vault as the spl tokens
+        bump,
+        token::mint = mint,
+        token::authority = state,
+        token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), mint.key().as_ref()],
+        seeds::program = metadata_program.key(), // the metadata pda belongs to the metadata program, not to us
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), mint.key().as_ref(), b"edition"],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub master_edition: Account<'info, MasterEditionAccount>, // only exists for a master edition, so fungibles can't get through
+
+    pub collection_mint: Option<InterfaceAccount<'info, Mint>>, // pass it to require the nft to be a verified member of this collection
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> NftPayment<'info> {
+    pub fn verify_collection(&self) -> Result<()> {
+        if let Some(collection_mint) = &self.collection_mint {
+            let collection = self.metadata.collection.as_ref().ok_or(VaultError::MissingCollection)?;
+            require!(collection.verified, VaultError::UnverifiedCollection);
+            require_keys_eq!(collection.key, collection_mint.key(), VaultError::WrongCollection);
+        }
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // withdrawing the nft closes its vault, so this one was just created by init_if_needed
+        self.state.open_account()?;
+
+        let accounts = TransferChecked {
+            from: self.owner_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault_ata.to_account_info(),
+            authority: self.owner.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked(cpi_ctx, 1, 0)?; // supply is 1 so there's only one to move
+
+        emit!(DepositEvent {
+            owner: self.owner.key(),
+            vault: self.vault_ata.key(),
+            mint: Some(self.mint.key()),
+            amount: 1,
+            balance: 1,
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        self.state.check_withdrawable()?;
+
+        let vault_id = self.state.vault_id.to_le_bytes();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"state",
+                self.state.creator.as_ref(),
+                &vault_id[..],
+                &[self.state.state_bump]
+            ]
+        ];
+
+        let accounts = TransferChecked {
+            from: self.vault_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.owner_ata.to_account_info(),
+            authority: self.state.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        ).with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked(cpi_ctx, 1, 0)?;
+
+        // the nft is the only thing that can be in there, so its vault is closed with it and the owner gets the rent back
+        harvest_withheld(
+            self.token_program.to_account_info(),
+            self.mint.to_account_info(),
+            self.vault_ata.to_account_info()
+        )?;
+
+        let close_accounts = CloseAccount {
+            account: self.vault_ata.to_account_info(),
+            destination: self.owner.to_account_info(),
+            authority: self.state.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_accounts,
+            &signer_seeds
+        );
+
+        close_account(cpi_ctx)?;
+        self.state.close_account();
+
+        emit!(WithdrawEvent {
+            owner: self.owner.key(),
+            vault: self.vault_ata.key(),
+            mint: Some(self.mint.key()),
+            amount: 1,
+            balance: 0,
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::VaultState, checks::{check_deposit, check_withdraw}, events::{DepositEvent, WithdrawEvent}};
+
+#[derive(Accounts)]
+pub struct Payment <'info>{
+    #[account(mut)] // someone needs to sign for the vault
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut, // withdrawals are counted against the cap
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner // only the owner saved in the state can move funds
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Payment<'info> {
+    pub fn deposit(&mut self, lamports: u64) -> Result<()> {
+        check_deposit(self.owner.lamports(), self.vault.lamports(), lamports)?;
+
+        let accounts = Transfer {
+            from: self.owner.to_account_info(),
+            to: self.vault.to_account_info(),
+        };
+
+        let transfer_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+        transfer(transfer_ctx, lamports)?;
+
+        emit!(DepositEvent {
+            owner: self.owner.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, lamports: u64) -> Result<()> {
+        self.state.check_withdrawable()?;
+        check_withdraw(self.vault.lamports(), lamports)?;
+        self.state.record_withdraw(lamports, Clock::get()?.unix_timestamp)?;
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.owner.to_account_info(),
+        };
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                state_key.as_ref(),
+                &[self.state.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer( // bc pda
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, lamports)?;
+
+        emit!(WithdrawEvent {
+            owner: self.owner.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{Pool, Position}, error::VaultError, checks::check_deposit, events::DepositEvent};
+
+#[derive(Accounts)]
+pub struct PoolDeposit <'info>{
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.creator.as_ref(), pool.seed.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init_if_needed, // first deposit into this pool opens the position
+        payer = depositor,
+        space = Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump = pool.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> PoolDeposit<'info> {
+    pub fn pool_deposit(&mut self, lamports: u64, bumps: &PoolDepositBumps) -> Result<()> {
+        check_deposit(self.depositor.lamports(), self.vault.lamports(), lamports)?;
+
+        // price the shares before the deposit lands in the vault
+        let shares = self.pool.shares_for_deposit(lamports, self.vault.lamports())?;
+        require!(shares > 0, VaultError::ZeroShares);
+
+        let accounts = Transfer {
+            from: self.depositor.to_account_info(),
+            to: self.vault.to_account_info(),
+        };
+
+        let transfer_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+        transfer(transfer_ctx, lamports)?;
+
+        self.pool.total_shares = self.pool.total_shares.checked_add(shares).ok_or(VaultError::Overflow)?;
+        self.position.pool = self.pool.key();
+        self.position.depositor = self.depositor.key();
+        self.position.shares += shares; // can't overflow if the pool total didn't
+        self.position.bump = bumps.position;
+
+        emit!(DepositEvent {
+            owner: self.pool.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{Pool, Position}, error::VaultError, checks::check_withdraw, events::WithdrawEvent};
+
+#[derive(Accounts)]
+pub struct PoolWithdraw <'info>{
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.creator.as_ref(), pool.seed.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), depositor.key().as_ref()],
+        bump = position.bump,
+        has_one = pool,
+        has_one = depositor
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump = pool.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> PoolWithdraw<'info> {
+    pub fn pool_withdraw(&mut self, shares: u64) -> Result<()> {
+        require!(shares > 0, VaultError::ZeroAmount);
+        require!(shares <= self.position.shares, VaultError::InsufficientShares);
+
+        let lamports = self.pool.lamports_for_shares(shares, self.vault.lamports())?;
+        check_withdraw(self.vault.lamports(), lamports)?;
+
+        self.position.shares -= shares;
+        self.pool.total_shares -= shares;
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.depositor.to_account_info(),
+        };
+
+        let pool_key = self.pool.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                pool_key.as_ref(),
+                &[self.pool.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, lamports)?;
+
+        emit!(WithdrawEvent {
+            owner: self.pool.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
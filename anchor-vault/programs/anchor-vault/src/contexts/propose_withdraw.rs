@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::{MultisigVault, WithdrawProposal};
+
+#[derive(Accounts)]
+pub struct ProposeWithdraw <'info>{
+    #[account(mut)]
+    pub proposer: Signer<'info>, // has to be one of the owners, checked in the handler
+
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.creator.as_ref(), multisig.seed.to_le_bytes().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, MultisigVault>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = WithdrawProposal::INIT_SPACE,
+        seeds = [b"proposal", multisig.key().as_ref(), multisig.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> ProposeWithdraw<'info> {
+    pub fn propose_withdraw(&mut self, recipient: Pubkey, amount: u64, bumps: &ProposeWithdrawBumps) -> Result<()> {
+        let owner_index = self.multisig.owner_index(&self.proposer.key())?;
+
+        // proposing counts as the proposer's approval
+        let mut approvals = vec![false; self.multisig.owners.len()];
+        approvals[owner_index] = true;
+
+        self.proposal.set_inner(WithdrawProposal {
+            multisig: self.multisig.key(),
+            index: self.multisig.proposal_count,
+            proposer: self.proposer.key(),
+            recipient,
+            amount,
+            approvals,
+            bump: bumps.proposal
+        });
+
+        self.multisig.proposal_count += 1;
+
+        Ok(())
+    }
+}
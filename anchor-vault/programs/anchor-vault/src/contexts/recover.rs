@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::{state::{VaultState, Recovery}, error::VaultError};
+
+#[derive(Accounts)]
+pub struct Recover <'info>{
+    pub recovery_key: Signer<'info>, // has to be one of the recovery keys, checked in the handler
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", state.key().as_ref()],
+        bump = recovery.bump,
+        has_one = state
+    )]
+    pub recovery: Account<'info, Recovery>,
+}
+
+impl<'info> Recover<'info> {
+    pub fn start_recovery(&mut self, new_owner: Pubkey) -> Result<()> {
+        let key_index = self.recovery.key_index(&self.recovery_key.key())?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            self.recovery.pending_owner.is_none() || self.recovery.is_stale(now),
+            VaultError::RecoveryPending
+        );
+
+        self.recovery.reset();
+        self.recovery.pending_owner = Some(new_owner);
+        self.recovery.approvals[key_index] = true; // starting counts as an approval
+        self.recovery.ready_ts = now + self.recovery.delay;
+
+        Ok(())
+    }
+
+    pub fn approve_recovery(&mut self) -> Result<()> {
+        let key_index = self.recovery.key_index(&self.recovery_key.key())?;
+        require!(self.recovery.pending_owner.is_some(), VaultError::NoRecoveryPending);
+        require!(!self.recovery.approvals[key_index], VaultError::AlreadyApproved);
+
+        self.recovery.approvals[key_index] = true;
+
+        Ok(())
+    }
+
+    pub fn complete_recovery(&mut self) -> Result<()> {
+        self.recovery.key_index(&self.recovery_key.key())?;
+        let new_owner = self.recovery.pending_owner.ok_or(VaultError::NoRecoveryPending)?;
+        require!(
+            self.recovery.approval_count() >= self.recovery.threshold as usize,
+            VaultError::NotEnoughApprovals
+        );
+        require!(Clock::get()?.unix_timestamp >= self.recovery.ready_ts, VaultError::RecoveryTooEarly);
+
+        self.state.owner = new_owner;
+        self.recovery.reset();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{VaultState, Delegation};
+
+#[derive(Accounts)]
+pub struct RevokeDelegate <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub delegate: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = owner, // the owner paid for the delegation so they get the rent back
+        seeds = [b"delegate", state.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.bump,
+        has_one = state,
+        has_one = delegate
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> RevokeDelegate<'info> {
+    pub fn revoke_delegate(&mut self) -> Result<()> {
+        // the delegation account itself is closed by the `close = owner` constraint
+        self.state.close_account();
+        Ok(())
+    }
+}
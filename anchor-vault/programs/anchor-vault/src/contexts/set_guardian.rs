@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::{state::{VaultState, GUARDIAN_CHANGE_DELAY}, error::VaultError};
+
+#[derive(Accounts)]
+pub struct SetGuardian <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+impl<'info> SetGuardian<'info> {
+    pub fn propose_guardian(&mut self, guardian: Option<Pubkey>) -> Result<()> {
+        if self.state.guardian.is_none() {
+            // a new guardian only adds protection, so there's nothing to wait for
+            self.state.guardian = guardian;
+            self.state.pending_guardian = None;
+            self.state.guardian_change_ts = 0;
+            return Ok(());
+        }
+
+        self.state.pending_guardian = guardian;
+        self.state.guardian_change_ts = Clock::get()?.unix_timestamp + GUARDIAN_CHANGE_DELAY;
+
+        Ok(())
+    }
+
+    pub fn apply_guardian(&mut self) -> Result<()> {
+        require!(self.state.guardian_change_ts != 0, VaultError::NoGuardianChange);
+        require!(Clock::get()?.unix_timestamp >= self.state.guardian_change_ts, VaultError::GuardianChangeTooEarly);
+
+        self.state.guardian = self.state.pending_guardian.take();
+        self.state.guardian_change_ts = 0;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::{state::{VaultState, Recovery, MAX_RECOVERY_KEYS}, error::VaultError};
+
+#[derive(Accounts)]
+pub struct SetRecovery <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Recovery::INIT_SPACE,
+        seeds = [b"recovery", state.key().as_ref()],
+        bump
+    )]
+    pub recovery: Account<'info, Recovery>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> SetRecovery<'info> {
+    pub fn set_recovery(&mut self, recovery_keys: Vec<Pubkey>, threshold: u8, delay: i64, bumps: &SetRecoveryBumps) -> Result<()> {
+        require!(recovery_keys.len() <= MAX_RECOVERY_KEYS, VaultError::TooManyRecoveryKeys);
+        require!(threshold > 0 && threshold as usize <= recovery_keys.len(), VaultError::InvalidThreshold);
+        require!(delay >= 0, VaultError::InvalidRecoveryDelay);
+        for (i, key) in recovery_keys.iter().enumerate() {
+            require!(!recovery_keys[..i].contains(key), VaultError::DuplicateRecoveryKey);
+        }
+
+        self.recovery.set_inner(Recovery {
+            state: self.state.key(),
+            approvals: vec![false; recovery_keys.len()],
+            recovery_keys,
+            threshold,
+            delay,
+            pending_owner: None,
+            ready_ts: 0,
+            bump: bumps.recovery
+        });
+
+        Ok(())
+    }
+
+    // the waiting period is there so a still active owner can stop a recovery they didn't ask for
+    pub fn cancel_recovery(&mut self) -> Result<()> {
+        require!(self.recovery.pending_owner.is_some(), VaultError::NoRecoveryPending);
+        self.recovery.reset();
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::{state::{VaultState, CAP_INCREASE_DELAY}, error::VaultError};
+
+#[derive(Accounts)]
+pub struct SetWithdrawCap <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+impl<'info> SetWithdrawCap<'info> {
+    pub fn set_withdraw_cap(&mut self, cap: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let current = self.state.withdraw_cap;
+
+        // a tighter cap only limits the owner, so it can apply straight away
+        let tighter = cap != 0 && (current == 0 || cap < current);
+        if tighter || cap == current {
+            if current == 0 {
+                self.state.window_start = now;
+                self.state.spent_in_window = 0;
+            }
+            self.state.withdraw_cap = cap;
+            self.state.pending_cap = 0;
+            self.state.cap_change_ts = 0;
+            return Ok(());
+        }
+
+        self.state.pending_cap = cap;
+        self.state.cap_change_ts = now + CAP_INCREASE_DELAY;
+
+        Ok(())
+    }
+
+    pub fn apply_withdraw_cap(&mut self) -> Result<()> {
+        require!(self.state.cap_change_ts != 0, VaultError::NoCapChange);
+        require!(Clock::get()?.unix_timestamp >= self.state.cap_change_ts, VaultError::CapChangeTooEarly);
+
+        self.state.withdraw_cap = self.state.pending_cap;
+        self.state.pending_cap = 0;
+        self.state.cap_change_ts = 0;
+
+        Ok(())
+    }
+}
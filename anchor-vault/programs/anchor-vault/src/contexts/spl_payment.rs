@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account}, associated_token::AssociatedToken};
+use crate::{
+    state::VaultState,
+    checks::check_token_withdraw,
+    error::VaultError,
+    events::{DepositEvent, WithdrawEvent},
+    transfer::{transfer_checked, net_amount, harvest_withheld}
+};
+
+#[derive(Accounts)]
+pub struct SplPayment <'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)] // harvesting withheld transfer fees before closing the token vault writes to the mint
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed, // the owner might be withdrawing a token they no longer hold an ata for
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut, // counts the token vault as open while it holds anything
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed, // one token vault per mint, created on the first deposit
+        payer = owner,
+        seeds = [b"vault", state.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = state, // same as the escrow, the state pda signs for the tokens
+        token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>, // spl token or token-2022, whichever owns the mint
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> SplPayment<'info> {
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>], amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::ZeroAmount);
+        require!(self.owner_ata.amount >= amount, VaultError::InsufficientFunds);
+
+        // a transfer fee comes out of what the vault receives, so only the net gets credited
+        let received = net_amount(&self.mint.to_account_info(), amount)?;
+        require!(received > 0, VaultError::ZeroAmount);
+
+        // the token vault is closed whenever it's emptied, so an empty one was just created by init_if_needed
+        if self.vault_ata.amount == 0 {
+            self.state.open_account()?;
+        }
+
+        let accounts = TransferChecked {
+            from: self.owner_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault_ata.to_account_info(),
+            authority: self.owner.to_account_info()
+        };
+
+        // remaining accounts are whatever the mint's transfer hook needs, empty for plain mints
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        emit!(DepositEvent {
+            owner: self.owner.key(),
+            vault: self.vault_ata.key(),
+            mint: Some(self.mint.key()),
+            amount: received,
+            balance: self.vault_ata.amount + received, // vault_ata still holds the data from before the transfer
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, remaining_accounts: &[AccountInfo<'info>], amount: u64) -> Result<()> {
+        self.state.check_withdrawable()?;
+        check_token_withdraw(self.vault_ata.amount, amount)?;
+
+        let vault_id = self.state.vault_id.to_le_bytes();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"state",
+                self.state.creator.as_ref(),
+                &vault_id[..],
+                &[self.state.state_bump]
+            ]
+        ];
+
+        let accounts = TransferChecked {
+            from: self.vault_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.owner_ata.to_account_info(),
+            authority: self.state.to_account_info()
+        };
+
+        // ! CpiContext::new_with_signer - the state pda is the authority of the token vault
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        ).with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?; // the vault pays the full amount, any fee comes off what the owner gets
+
+        // an emptied token vault is closed so close() isn't blocked by it, the owner paid for it so they get the rent
+        if amount == self.vault_ata.amount {
+            harvest_withheld(
+                self.token_program.to_account_info(),
+                self.mint.to_account_info(),
+                self.vault_ata.to_account_info()
+            )?;
+
+            let close_accounts = CloseAccount {
+                account: self.vault_ata.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.state.to_account_info()
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                close_accounts,
+                &signer_seeds
+            );
+
+            close_account(cpi_ctx)?;
+            self.state.close_account();
+        }
+
+        emit!(WithdrawEvent {
+            owner: self.owner.key(),
+            vault: self.vault_ata.key(),
+            mint: Some(self.mint.key()),
+            amount,
+            balance: self.vault_ata.amount - amount,
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
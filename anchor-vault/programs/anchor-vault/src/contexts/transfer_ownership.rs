@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::{VaultState, Recovery};
+
+#[derive(Accounts)]
+pub struct TransferOwnership <'info>{
+    #[account(mut)] // gets the recovery account's rent back, they paid for it
+    pub owner: Signer<'info>,
+
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", state.key().as_ref()],
+        bump
+    )]
+    /// CHECK: the seeds make sure it's this vault's recovery account, it may not exist
+    pub recovery: UncheckedAccount<'info>,
+}
+
+impl<'info> TransferOwnership<'info> {
+    pub fn transfer_ownership(&mut self) -> Result<()> {
+        // the vault and token vaults hang off the state key so nothing has to move
+        self.state.owner = self.new_owner.key();
+
+        // the old owner's recovery keys shouldn't be able to take the vault from the new one,
+        // that includes finishing a recovery they started before the transfer
+        Recovery::close_if_set(&self.recovery.to_account_info(), &self.owner.to_account_info())
+    }
+}
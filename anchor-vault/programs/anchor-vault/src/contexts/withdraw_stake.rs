@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, stake::{self, instruction as stake_instruction}, sysvar};
+use crate::{state::VaultState, error::VaultError, events::DepositEvent};
+
+#[derive(Accounts)]
+pub struct WithdrawStake <'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump,
+        has_one = owner,
+        constraint = state.staked_lamports > 0 @ VaultError::NotStaked
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", state.key().as_ref()],
+        bump
+    )]
+    /// CHECK: the seeds make sure it's the vault's stake account
+    pub stake_account: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    #[account(address = sysvar::stake_history::ID)]
+    /// CHECK: address is checked
+    pub stake_history: UncheckedAccount<'info>,
+    #[account(address = stake::program::ID)]
+    /// CHECK: address is checked
+    pub stake_program: UncheckedAccount<'info>
+}
+
+impl<'info> WithdrawStake<'info> {
+    pub fn withdraw_stake(&mut self) -> Result<()> {
+        // take everything, the stake program only allows it once the stake is inactive
+        let lamports = self.stake_account.lamports();
+        let ix = stake_instruction::withdraw(
+            &self.stake_account.key(),
+            &self.vault.key(),
+            &self.vault.key(),
+            lamports,
+            None
+        );
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[b"vault", state_key.as_ref(), &[self.state.vault_bump]]
+        ];
+
+        invoke_signed(
+            &ix,
+            &[
+                self.stake_account.to_account_info(),
+                self.vault.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_program.to_account_info()
+            ],
+            &signer_seeds
+        )?;
+
+        self.state.staked_lamports = 0;
+
+        // the stake and its rewards are back in the vault
+        emit!(DepositEvent {
+            owner: self.owner.key(),
+            vault: self.vault.key(),
+            mint: None,
+            amount: lamports,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
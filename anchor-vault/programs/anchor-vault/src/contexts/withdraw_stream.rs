@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::{state::{VaultState, Stream}, error::VaultError, checks::check_withdraw, events::PayoutEvent};
+
+#[derive(Accounts)]
+pub struct WithdrawStream <'info>{
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", state.creator.as_ref(), state.vault_id.to_le_bytes().as_ref()],
+        bump = state.state_bump
+    )]
+    pub state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"stream", state.key().as_ref(), recipient.key().as_ref(), stream.seed.to_le_bytes().as_ref()],
+        bump = stream.bump,
+        has_one = state,
+        has_one = recipient
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", state.key().as_ref()],
+        bump = state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> WithdrawStream<'info> {
+    pub fn withdraw_stream(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let amount = self.stream.accrued_amount(now)?;
+        require!(amount > 0, VaultError::NothingToClaim);
+
+        // a stream is paid by the owner's vault so it goes through the same checks as the owner
+        self.state.check_withdrawable()?;
+        check_withdraw(self.vault.lamports(), amount)?;
+        self.state.record_withdraw(amount, now)?;
+
+        self.stream.withdrawn += amount;
+
+        let accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.recipient.to_account_info(),
+        };
+
+        let state_key = self.state.key();
+        let signer_seeds: [&[&[u8]]; 1] = [
+            &[
+                b"vault",
+                state_key.as_ref(),
+                &[self.state.vault_bump]
+            ]
+        ];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            &signer_seeds
+        );
+
+        transfer(transfer_ctx, amount)?;
+
+        emit!(PayoutEvent {
+            owner: self.state.owner,
+            vault: self.vault.key(),
+            recipient: self.recipient.key(),
+            amount,
+            balance: self.vault.lamports(),
+            slot: Clock::get()?.slot
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Mint is not an NFT, it needs a supply of 1 and 0 decimals")]
+    NotAnNft,
+    #[msg("NFT is not part of a collection")]
+    MissingCollection,
+    #[msg("NFT collection is not verified")]
+    UnverifiedCollection,
+    #[msg("NFT belongs to a different collection")]
+    WrongCollection,
+    #[msg("Vault is locked, try again after the unlock timestamp")]
+    VaultLocked,
+    #[msg("Lock can only be extended, not shortened")]
+    LockShortened,
+    #[msg("Vesting needs a non zero amount and start <= cliff <= end with start < end")]
+    InvalidSchedule,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("Vesting asset doesn't match this instruction")]
+    WrongVestingAsset,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Too many multisig owners")]
+    TooManyOwners,
+    #[msg("Multisig owners must be unique")]
+    DuplicateOwner,
+    #[msg("Signer is not an owner of this multisig")]
+    NotAnOwner,
+    #[msg("Owner already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal doesn't have enough approvals yet")]
+    NotEnoughApprovals,
+    #[msg("Withdrawal is over the delegate's allowance")]
+    AllowanceExceeded,
+    #[msg("Delegation period can't be negative")]
+    InvalidPeriod,
+    #[msg("Amount must be greater than 0")]
+    ZeroAmount,
+    #[msg("Not enough funds for this transfer")]
+    InsufficientFunds,
+    #[msg("Vault would be left below the rent exempt minimum, withdraw everything or leave enough for rent")]
+    WouldBreakRentExemption,
+    #[msg("Amount overflowed")]
+    Overflow,
+    #[msg("Vault already has an active stake account")]
+    AlreadyStaked,
+    #[msg("Vault has no stake account")]
+    NotStaked,
+    #[msg("Deposit is too small to mint any pool shares")]
+    ZeroShares,
+    #[msg("Not enough pool shares")]
+    InsufficientShares,
+    #[msg("Vault is frozen by its guardian")]
+    VaultFrozen,
+    #[msg("Signer is not the vault guardian")]
+    NotGuardian,
+    #[msg("No guardian change is pending")]
+    NoGuardianChange,
+    #[msg("Guardian change delay hasn't passed yet")]
+    GuardianChangeTooEarly,
+    #[msg("Too many recovery keys")]
+    TooManyRecoveryKeys,
+    #[msg("Recovery keys must be unique")]
+    DuplicateRecoveryKey,
+    #[msg("Recovery delay can't be negative")]
+    InvalidRecoveryDelay,
+    #[msg("Signer is not a recovery key of this vault")]
+    NotARecoveryKey,
+    #[msg("Another recovery is already pending, it can be replaced once its approval window has passed")]
+    RecoveryPending,
+    #[msg("No recovery is pending")]
+    NoRecoveryPending,
+    #[msg("Recovery delay hasn't passed yet")]
+    RecoveryTooEarly,
+    #[msg("Withdrawal is over the vault's cap for this window")]
+    WithdrawCapExceeded,
+    #[msg("No withdraw cap change is pending")]
+    NoCapChange,
+    #[msg("Withdraw cap increase delay hasn't passed yet")]
+    CapChangeTooEarly,
+    #[msg("Batch needs one amount per recipient account")]
+    BatchLengthMismatch,
+    #[msg("Batch has no payouts")]
+    EmptyBatch,
+    #[msg("Batch recipient must be writable")]
+    RecipientNotWritable,
+    #[msg("Stream needs a non zero rate and start < end")]
+    InvalidStream,
+    #[msg("Too many vaults for one wallet")]
+    TooManyVaults,
+    #[msg("Vault still has open delegations, streams or token vaults, revoke, cancel or withdraw them before closing")]
+    OpenAccounts,
+}
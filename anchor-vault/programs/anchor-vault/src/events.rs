@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+// owner is whoever controls the vault: the vault owner, or the multisig / vesting / pool account for those vaults
+
+#[event]
+pub struct DepositEvent {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Option<Pubkey>, // None for lamports
+    pub amount: u64,
+    pub balance: u64, // vault balance after the deposit
+    pub slot: u64
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub balance: u64, // vault balance after the withdraw
+    pub slot: u64
+}
+
+#[event]
+pub struct PayoutEvent {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub balance: u64, // vault balance after this payout
+    pub slot: u64
+}
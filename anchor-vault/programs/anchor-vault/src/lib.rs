@@ -1,5 +1,17 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{Transfer, transfer};
+
+pub mod contexts;
+use contexts::*;
+
+pub mod state;
+
+pub mod error;
+
+pub mod checks;
+
+pub mod transfer;
+
+pub mod events;
 
 declare_id!("9ri4ddvn5PVouDM1eX4KhCo4a3SAcrNKyKgunKce43Gm");
 
@@ -7,53 +19,208 @@ declare_id!("9ri4ddvn5PVouDM1eX4KhCo4a3SAcrNKyKgunKce43Gm");
 pub mod anchor_vault {
     use super::*;
 
-    pub fn deposit(ctx: Context<Vault>, lamports: u64) -> Result<()> {
-        let accounts = Transfer {
-            from: ctx.accounts.signer.to_account_info(),
-            to: ctx.accounts.vault.to_account_info(),
-        };
+    // pass 0 as unlock_ts for a vault without a time lock
+    pub fn initialize(ctx: Context<Initialize>, vault_id: u64, unlock_ts: i64) -> Result<()> {
+        ctx.accounts.initialize(vault_id, unlock_ts, &ctx.bumps)
+    }
+
+    pub fn extend_lock(ctx: Context<ExtendLock>, unlock_ts: i64) -> Result<()> {
+        ctx.accounts.extend_lock(unlock_ts)
+    }
+
+    pub fn deposit(ctx: Context<Payment>, lamports: u64) -> Result<()> {
+        ctx.accounts.deposit(lamports)
+    }
 
-        let transfer_ctx = CpiContext::new(ctx.accounts.system_progam.to_account_info() , accounts);
+    pub fn withdraw(ctx: Context<Payment>, lamports: u64) -> Result<()> {
+        ctx.accounts.withdraw(lamports)
+    }
 
-        transfer(transfer_ctx, lamports)
+    pub fn deposit_spl<'info>(ctx: Context<'_, '_, '_, 'info, SplPayment<'info>>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit(ctx.remaining_accounts, amount)
     }
 
-    pub fn withdraw(ctx: Context<Vault>, lamports: u64) -> Result<()> {
-        // we could remove lamports and have withdraw be a function that empties and closes the vault
-        let accounts = Transfer {
-            from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.signer.to_account_info(),
-        };
+    pub fn withdraw_spl<'info>(ctx: Context<'_, '_, '_, 'info, SplPayment<'info>>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw(ctx.remaining_accounts, amount)
+    }
+
+    pub fn deposit_nft<'info>(ctx: Context<'_, '_, '_, 'info, NftPayment<'info>>) -> Result<()> {
+        ctx.accounts.verify_collection()?;
+        ctx.accounts.deposit(ctx.remaining_accounts)
+    }
 
-        let seeds = &[
-            b"vault",
-            ctx.accounts.signer.to_account_info().key.as_ref(),
-            &[ctx.bumps.vault]
-            ];
+    pub fn withdraw_nft<'info>(ctx: Context<'_, '_, '_, 'info, NftPayment<'info>>) -> Result<()> {
+        ctx.accounts.withdraw(ctx.remaining_accounts)
+    }
 
-        let signer_seeds = &[&seeds[..]];
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        seed: u64,
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64
+    ) -> Result<()> {
+        ctx.accounts.save(seed, amount, start_ts, cliff_ts, end_ts, &ctx.bumps)?;
+        ctx.accounts.deposit(amount)
+    }
 
-        let transfer_ctx = CpiContext::new_with_signer( // bc pda
-            ctx.accounts.system_progam.to_account_info(),
-            accounts,
-            signer_seeds
-        );
+    pub fn create_vesting_spl<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateVestingSpl<'info>>,
+        seed: u64,
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64
+    ) -> Result<()> {
+        ctx.accounts.save(seed, amount, start_ts, cliff_ts, end_ts, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts, amount)
+    }
 
-        transfer(transfer_ctx, lamports)
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        ctx.accounts.claim()
     }
 
-}
+    pub fn claim_spl<'info>(ctx: Context<'_, '_, '_, 'info, ClaimSpl<'info>>) -> Result<()> {
+        ctx.accounts.claim(ctx.remaining_accounts)
+    }
+
+    pub fn create_multisig(ctx: Context<CreateMultisig>, seed: u64, owners: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        ctx.accounts.create_multisig(seed, owners, threshold, &ctx.bumps)
+    }
+
+    pub fn propose_withdraw(ctx: Context<ProposeWithdraw>, recipient: Pubkey, lamports: u64) -> Result<()> {
+        ctx.accounts.propose_withdraw(recipient, lamports, &ctx.bumps)
+    }
+
+    pub fn approve_withdraw(ctx: Context<ApproveWithdraw>) -> Result<()> {
+        ctx.accounts.approve_withdraw()
+    }
+
+    pub fn execute_withdraw(ctx: Context<ExecuteWithdraw>) -> Result<()> {
+        ctx.accounts.execute_withdraw()
+    }
+
+    // proposals don't expire, the proposer cancels one that shouldn't go through anymore
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+        ctx.accounts.cancel_withdraw()
+    }
+
+    // approving an existing delegate again resets its allowance and spent amount
+    pub fn approve_delegate(ctx: Context<ApproveDelegate>, allowance: u64, period: i64) -> Result<()> {
+        ctx.accounts.approve_delegate(allowance, period, &ctx.bumps)
+    }
+
+    pub fn delegate_withdraw(ctx: Context<DelegateWithdraw>, lamports: u64) -> Result<()> {
+        ctx.accounts.delegate_withdraw(lamports)
+    }
+
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        ctx.accounts.revoke_delegate()
+    }
+
+    // moves lamports from the vault into a stake account owned by the vault pda and delegates them
+    pub fn delegate_stake(ctx: Context<DelegateStake>, lamports: u64) -> Result<()> {
+        ctx.accounts.create_stake_account(lamports, &ctx.bumps)?;
+        ctx.accounts.delegate_stake()
+    }
+
+    pub fn deactivate_stake(ctx: Context<DeactivateStake>) -> Result<()> {
+        ctx.accounts.deactivate_stake()
+    }
 
+    // only works once the stake is fully deactivated, brings everything including rewards back to the vault
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        ctx.accounts.withdraw_stake()
+    }
+
+    pub fn create_pool(ctx: Context<CreatePool>, seed: u64) -> Result<()> {
+        ctx.accounts.create_pool(seed, &ctx.bumps)
+    }
+
+    pub fn pool_deposit(ctx: Context<PoolDeposit>, lamports: u64) -> Result<()> {
+        ctx.accounts.pool_deposit(lamports, &ctx.bumps)
+    }
+
+    // redeems shares at the current pool ratio
+    pub fn pool_withdraw(ctx: Context<PoolWithdraw>, shares: u64) -> Result<()> {
+        ctx.accounts.pool_withdraw(shares)
+    }
+
+    pub fn freeze(ctx: Context<Freeze>) -> Result<()> {
+        ctx.accounts.set_frozen(true)
+    }
+
+    pub fn unfreeze(ctx: Context<Freeze>) -> Result<()> {
+        ctx.accounts.set_frozen(false)
+    }
+
+    // adding the first guardian is instant, replacing or removing one waits GUARDIAN_CHANGE_DELAY
+    pub fn propose_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.propose_guardian(guardian)
+    }
+
+    pub fn apply_guardian(ctx: Context<SetGuardian>) -> Result<()> {
+        ctx.accounts.apply_guardian()
+    }
+
+    // the new owner signs too so a typo can't lock the vault
+    pub fn transfer_ownership(ctx: Context<TransferOwnership>) -> Result<()> {
+        ctx.accounts.transfer_ownership()
+    }
+
+    // setting the recovery keys again cancels any pending recovery
+    pub fn set_recovery(ctx: Context<SetRecovery>, recovery_keys: Vec<Pubkey>, threshold: u8, delay: i64) -> Result<()> {
+        ctx.accounts.set_recovery(recovery_keys, threshold, delay, &ctx.bumps)
+    }
+
+    pub fn cancel_recovery(ctx: Context<SetRecovery>) -> Result<()> {
+        ctx.accounts.cancel_recovery()
+    }
+
+    pub fn start_recovery(ctx: Context<Recover>, new_owner: Pubkey) -> Result<()> {
+        ctx.accounts.start_recovery(new_owner)
+    }
+
+    pub fn approve_recovery(ctx: Context<Recover>) -> Result<()> {
+        ctx.accounts.approve_recovery()
+    }
+
+    pub fn complete_recovery(ctx: Context<Recover>) -> Result<()> {
+        ctx.accounts.complete_recovery()
+    }
+
+    // lowering the cap is instant, raising or removing it (cap = 0) waits CAP_INCREASE_DELAY
+    pub fn set_withdraw_cap(ctx: Context<SetWithdrawCap>, cap: u64) -> Result<()> {
+        ctx.accounts.set_withdraw_cap(cap)
+    }
+
+    pub fn apply_withdraw_cap(ctx: Context<SetWithdrawCap>) -> Result<()> {
+        ctx.accounts.apply_withdraw_cap()
+    }
+
+    // recipients are passed as writable remaining accounts, in the same order as amounts
+    pub fn batch_withdraw<'info>(ctx: Context<'_, '_, '_, 'info, BatchWithdraw<'info>>, amounts: Vec<u64>) -> Result<()> {
+        ctx.accounts.batch_withdraw(ctx.remaining_accounts, amounts)
+    }
+
+    pub fn create_stream(ctx: Context<CreateStream>, seed: u64, rate: u64, start_ts: i64, end_ts: i64) -> Result<()> {
+        ctx.accounts.create_stream(seed, rate, start_ts, end_ts, &ctx.bumps)
+    }
 
-#[derive(Accounts)]
-pub struct Vault <'info>{
-    #[account(mut)] // someone needs to sign for the vault
-    signer: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [b"vault", signer.key().as_ref()],
-        bump
-    )]
-    vault: SystemAccount<'info>,
-    system_progam: Program<'info, System>
+    // permissionless, the recipient or any crank can push what has accrued to the recipient
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        ctx.accounts.withdraw_stream()
+    }
+
+    // pays the recipient what has accrued so far and closes the stream
+    // if the vault can't pay right now the stream just stops accruing and stays open until it's settled
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        ctx.accounts.cancel_stream()
+    }
+
+    pub fn close(ctx: Context<Close>) -> Result<()> {
+        // empty the vault first, the state account is closed by the `close = owner` constraint
+        ctx.accounts.close()
+    }
 }
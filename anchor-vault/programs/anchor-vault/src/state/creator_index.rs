@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+pub const MAX_VAULTS: usize = 16;
+
+// lists the vault ids a wallet created, so a client can find the state pdas with one fetch
+// it's a creator index on purpose: the ids are only unique per creator, and an ownership transfer
+// or a recovery doesn't move a vault out of it. vaults a wallet owns now are found by the owner
+// field of VaultState instead (see fetch_owned_states in the client)
+#[account]
+pub struct CreatorIndex {
+    pub creator: Pubkey,
+    pub vault_ids: Vec<u64>,
+    pub bump: u8
+}
+
+impl Space for CreatorIndex {
+    const INIT_SPACE: usize = 8 + 32 + (4 + 8 * MAX_VAULTS) + 1;
+}
+
+impl CreatorIndex {
+    pub fn add(&mut self, vault_id: u64) -> Result<()> {
+        require!(self.vault_ids.len() < MAX_VAULTS, VaultError::TooManyVaults);
+        self.vault_ids.push(vault_id); // can't be a duplicate, the state pda for it would already exist
+        Ok(())
+    }
+
+    pub fn remove(&mut self, vault_id: u64) {
+        self.vault_ids.retain(|id| *id != vault_id);
+    }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+#[account]
+pub struct Delegation {
+    pub state: Pubkey, // the vault this allowance is drawn from
+    pub delegate: Pubkey,
+    pub allowance: u64, // lamports the delegate can spend per period
+    pub period: i64, // length of a period in seconds, 0 means the allowance never refills
+    pub period_start: i64,
+    pub spent: u64, // spent in the current period
+    pub bump: u8
+}
+
+impl Space for Delegation {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+}
+
+impl Delegation {
+    pub fn spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        if self.period > 0 && now >= self.period_start + self.period {
+            // move to the current period, aligned to the original start so it doesn't drift
+            let elapsed_periods = (now - self.period_start) / self.period;
+            self.period_start += elapsed_periods * self.period;
+            self.spent = 0;
+        }
+
+        let spent = self.spent.checked_add(amount).ok_or(VaultError::AllowanceExceeded)?;
+        require!(spent <= self.allowance, VaultError::AllowanceExceeded);
+        self.spent = spent;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 100 lamports a day starting at t = 1000
+    fn delegation(period: i64) -> Delegation {
+        Delegation {
+            state: Pubkey::default(),
+            delegate: Pubkey::default(),
+            allowance: 100,
+            period,
+            period_start: 1000,
+            spent: 0,
+            bump: 0
+        }
+    }
+
+    #[test]
+    fn rejects_overspend() {
+        let mut delegation = delegation(86_400);
+        delegation.spend(60, 1000).unwrap();
+        assert_eq!(delegation.spend(41, 1000).unwrap_err(), VaultError::AllowanceExceeded.into());
+        // a failed spend doesn't count against the allowance
+        assert_eq!(delegation.spent, 60);
+        delegation.spend(40, 1000).unwrap();
+        assert_eq!(delegation.spend(u64::MAX, 1000).unwrap_err(), VaultError::AllowanceExceeded.into());
+    }
+
+    #[test]
+    fn refills_after_a_period() {
+        let mut delegation = delegation(86_400);
+        delegation.spend(100, 1000).unwrap();
+        // one second before the period ends it's still used up
+        assert_eq!(delegation.spend(1, 1000 + 86_399).unwrap_err(), VaultError::AllowanceExceeded.into());
+
+        // a few periods later the start stays aligned to the original one
+        delegation.spend(100, 1000 + 3 * 86_400 + 5).unwrap();
+        assert_eq!(delegation.period_start, 1000 + 3 * 86_400);
+        assert_eq!(delegation.spent, 100);
+    }
+
+    #[test]
+    fn zero_period_never_refills() {
+        let mut delegation = delegation(0);
+        delegation.spend(100, 1000).unwrap();
+        assert_eq!(delegation.spend(1, i64::MAX).unwrap_err(), VaultError::AllowanceExceeded.into());
+        assert_eq!(delegation.period_start, 1000);
+    }
+}
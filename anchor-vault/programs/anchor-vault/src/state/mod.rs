@@ -0,0 +1,23 @@
+pub mod vault_state;
+pub use vault_state::*;
+
+pub mod vesting;
+pub use vesting::*;
+
+pub mod multisig;
+pub use multisig::*;
+
+pub mod delegation;
+pub use delegation::*;
+
+pub mod pool;
+pub use pool::*;
+
+pub mod recovery;
+pub use recovery::*;
+
+pub mod stream;
+pub use stream::*;
+
+pub mod creator_index;
+pub use creator_index::*;
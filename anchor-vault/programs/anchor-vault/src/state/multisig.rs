@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+pub const MAX_OWNERS: usize = 10;
+
+#[account]
+pub struct MultisigVault {
+    pub creator: Pubkey, // only used in the seeds, the creator has no extra rights
+    pub seed: u64,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8, // M approvals out of owners.len() needed to withdraw
+    pub proposal_count: u64, // gives every proposal its own address
+    pub bump: u8,
+    pub vault_bump: u8
+}
+
+impl Space for MultisigVault {
+    const INIT_SPACE: usize = 8 + 32 + 8 + (4 + 32 * MAX_OWNERS) + 1 + 8 + 1 + 1; // a Vec is a 4 byte length + the items
+}
+
+impl MultisigVault {
+    pub fn owner_index(&self, key: &Pubkey) -> Result<usize> {
+        self.owners.iter().position(|owner| owner == key).ok_or(error!(VaultError::NotAnOwner))
+    }
+}
+
+#[account]
+pub struct WithdrawProposal {
+    pub multisig: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey, // gets the rent back once the proposal is executed
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub approvals: Vec<bool>, // same order as the multisig owners
+    pub bump: u8
+}
+
+impl Space for WithdrawProposal {
+    const INIT_SPACE: usize = 8 + 32 + 8 + 32 + 32 + 8 + (4 + MAX_OWNERS) + 1;
+}
+
+impl WithdrawProposal {
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+}
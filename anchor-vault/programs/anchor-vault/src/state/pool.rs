@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+#[account]
+pub struct Pool {
+    pub creator: Pubkey, // only used in the seeds
+    pub seed: u64,
+    pub total_shares: u64,
+    pub bump: u8,
+    pub vault_bump: u8
+}
+
+impl Space for Pool {
+    const INIT_SPACE: usize = 8 + 32 + 8 + 8 + 1 + 1;
+}
+
+impl Pool {
+    // the first deposit sets the price at 1 share per lamport, after that it follows the pool balance
+    pub fn shares_for_deposit(&self, lamports: u64, pool_balance: u64) -> Result<u64> {
+        if self.total_shares == 0 || pool_balance == 0 {
+            return Ok(lamports);
+        }
+
+        let shares = lamports as u128 * self.total_shares as u128 / pool_balance as u128;
+        u64::try_from(shares).map_err(|_| error!(VaultError::Overflow))
+    }
+
+    pub fn lamports_for_shares(&self, shares: u64, pool_balance: u64) -> Result<u64> {
+        require!(shares <= self.total_shares, VaultError::InsufficientShares);
+
+        let lamports = shares as u128 * pool_balance as u128 / self.total_shares as u128;
+        u64::try_from(lamports).map_err(|_| error!(VaultError::Overflow))
+    }
+}
+
+#[account]
+pub struct Position {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub shares: u64,
+    pub bump: u8
+}
+
+impl Space for Position {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 1;
+}
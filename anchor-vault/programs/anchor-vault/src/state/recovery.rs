@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::error::VaultError;
+
+pub const MAX_RECOVERY_KEYS: usize = 5;
+
+// a pending recovery that doesn't reach the threshold in this long can be replaced by a new one
+pub const RECOVERY_APPROVAL_WINDOW: i64 = 7 * 24 * 60 * 60;
+
+#[account]
+pub struct Recovery {
+    pub state: Pubkey,
+    pub recovery_keys: Vec<Pubkey>,
+    pub threshold: u8, // K recovery keys needed to reassign the owner
+    pub delay: i64, // seconds the owner has to cancel a recovery
+    pub pending_owner: Option<Pubkey>,
+    pub approvals: Vec<bool>, // same order as recovery_keys
+    pub ready_ts: i64, // when the pending recovery can be completed
+    pub bump: u8
+}
+
+impl Space for Recovery {
+    const INIT_SPACE: usize = 8 + 32 + (4 + 32 * MAX_RECOVERY_KEYS) + 1 + 8 + (1 + 32) + (4 + MAX_RECOVERY_KEYS) + 8 + 1;
+}
+
+impl Recovery {
+    pub fn key_index(&self, key: &Pubkey) -> Result<usize> {
+        self.recovery_keys.iter().position(|recovery_key| recovery_key == key).ok_or(error!(VaultError::NotARecoveryKey))
+    }
+
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+
+    // otherwise one bad recovery key could start a recovery nobody approves and block every other one for good
+    pub fn is_stale(&self, now: i64) -> bool {
+        let started_ts = self.ready_ts - self.delay;
+        self.approval_count() < self.threshold as usize && now >= started_ts + RECOVERY_APPROVAL_WINDOW
+    }
+
+    pub fn reset(&mut self) {
+        self.pending_owner = None;
+        self.approvals = vec![false; self.recovery_keys.len()];
+        self.ready_ts = 0;
+    }
+
+    // the instructions that drop the recovery keys take the account unchecked since it may not exist
+    pub fn close_if_set(recovery: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+        if recovery.owner != &crate::ID {
+            return Ok(()); // recovery was never set up
+        }
+
+        // same as what anchor's close constraint does
+        **destination.try_borrow_mut_lamports()? += recovery.lamports();
+        **recovery.try_borrow_mut_lamports()? = 0;
+        recovery.assign(&system_program::ID);
+        recovery.realloc(0, false).map_err(Into::into)
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+#[account]
+pub struct Stream {
+    pub state: Pubkey, // the vault paying the stream
+    pub recipient: Pubkey,
+    pub seed: u64,
+    pub rate: u64, // lamports per second
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub withdrawn: u64,
+    pub bump: u8
+}
+
+impl Space for Stream {
+    const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+impl Stream {
+    // accrued but not yet paid out
+    pub fn accrued_amount(&self, now: i64) -> Result<u64> {
+        let elapsed = now.min(self.end_ts) - self.start_ts;
+        if elapsed <= 0 {
+            return Ok(0);
+        }
+
+        let streamed = self.rate.checked_mul(elapsed as u64).ok_or(VaultError::Overflow)?;
+        Ok(streamed - self.withdrawn)
+    }
+}
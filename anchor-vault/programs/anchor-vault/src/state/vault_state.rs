@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+#[account]
+pub struct VaultState {
+    pub creator: Pubkey, // used in the seeds instead of the owner so the owner can be rotated
+    pub vault_id: u64, // also in the seeds, one creator can have up to MAX_VAULTS open at once, the creator index rejects more with TooManyVaults
+    pub owner: Pubkey,
+    pub state_bump: u8,
+    pub vault_bump: u8, // saved so we don't have to find the vault bump again on every withdraw
+    pub created_at: i64,
+    pub unlock_ts: i64, // withdrawals are locked until this unix timestamp, 0 means no lock
+    pub staked_lamports: u64, // lamports sitting in the stake account, the liquid ones are the vault balance
+    pub guardian: Option<Pubkey>, // can freeze the vault if the owner key is compromised
+    pub frozen: bool,
+    pub pending_guardian: Option<Pubkey>,
+    pub guardian_change_ts: i64, // when pending_guardian can be applied, 0 means no change pending
+    pub withdraw_cap: u64, // lamports that can leave the vault per window, 0 means no cap
+    pub window_start: i64,
+    pub spent_in_window: u64,
+    pub pending_cap: u64,
+    pub cap_change_ts: i64, // when pending_cap can be applied, 0 means no change pending
+    pub open_accounts: u16 // delegations, streams and token vaults still pointing at this state, close waits for them so they can't outlive it
+}
+
+impl Space for VaultState {
+    const INIT_SPACE: usize = 8 + 32 + 8 + 32 + 1 + 1 + 8 + 8 + 8 + (1 + 32) + 1 + (1 + 32) + 8 + 8 + 8 + 8 + 8 + 8 + 2; // anchor adds a discriminator of 8 bytes
+}
+
+// gives the guardian time to react before the owner can replace or remove them
+pub const GUARDIAN_CHANGE_DELAY: i64 = 2 * 24 * 60 * 60;
+
+pub const WITHDRAW_WINDOW: i64 = 24 * 60 * 60;
+
+// a stolen key can't lift the cap right away, the owner has a day to notice
+pub const CAP_INCREASE_DELAY: i64 = 24 * 60 * 60;
+
+impl VaultState {
+    // a re-initialized vault gets the same state pda, so anything left pointing at the old one would come back to life
+    pub fn open_account(&mut self) -> Result<()> {
+        self.open_accounts = self.open_accounts.checked_add(1).ok_or(VaultError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn close_account(&mut self) {
+        self.open_accounts -= 1;
+    }
+
+    // every path that moves funds out of the vault goes through this
+    pub fn check_withdrawable(&self) -> Result<()> {
+        require!(!self.frozen, VaultError::VaultFrozen);
+        require!(Clock::get()?.unix_timestamp >= self.unlock_ts, VaultError::VaultLocked);
+        Ok(())
+    }
+
+    // counts lamports leaving the vault against the withdraw cap
+    pub fn record_withdraw(&mut self, lamports: u64, now: i64) -> Result<()> {
+        if self.withdraw_cap == 0 {
+            return Ok(());
+        }
+
+        if now >= self.window_start + WITHDRAW_WINDOW {
+            // same as the delegations, keep the windows aligned to the first one
+            let elapsed_windows = (now - self.window_start) / WITHDRAW_WINDOW;
+            self.window_start += elapsed_windows * WITHDRAW_WINDOW;
+            self.spent_in_window = 0;
+        }
+
+        let spent = self.spent_in_window.checked_add(lamports).ok_or(VaultError::WithdrawCapExceeded)?;
+        require!(spent <= self.withdraw_cap, VaultError::WithdrawCapExceeded);
+        self.spent_in_window = spent;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::error::VaultError;
+
+#[account]
+pub struct Vesting {
+    pub funder: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Option<Pubkey>, // None when we're vesting lamports
+    pub seed: u64, // lets a funder open several schedules for the same beneficiary
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub bump: u8,
+    pub vault_bump: u8
+}
+
+impl Space for Vesting {
+    const INIT_SPACE: usize = 8 + 32 + 32 + (1 + 32) + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1; // an Option adds 1 byte for the tag
+}
+
+impl Vesting {
+    pub fn check_schedule(&self) -> Result<()> {
+        require!(self.total_amount > 0, VaultError::InvalidSchedule);
+        require!(self.start_ts <= self.cliff_ts && self.cliff_ts <= self.end_ts, VaultError::InvalidSchedule);
+        require!(self.start_ts < self.end_ts, VaultError::InvalidSchedule);
+        Ok(())
+    }
+
+    // linear from start to end, but nothing unlocks before the cliff
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now < self.cliff_ts {
+            return 0;
+        }
+        if now >= self.end_ts {
+            return self.total_amount;
+        }
+
+        let elapsed = (now - self.start_ts) as u128;
+        let duration = (self.end_ts - self.start_ts) as u128;
+        (self.total_amount as u128 * elapsed / duration) as u64 // u128 so the multiplication can't overflow
+    }
+
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now).saturating_sub(self.claimed_amount)
+    }
+
+    pub fn is_fully_claimed(&self) -> bool {
+        self.claimed_amount == self.total_amount
+    }
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::{
+    token_interface::TransferChecked,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            BaseStateWithExtensions, StateWithExtensions,
+            transfer_fee::{TransferFeeConfig, TransferFeeAmount, instruction::harvest_withheld_tokens_to_mint}
+        },
+        onchain::invoke_transfer_checked
+    }
+};
+use crate::error::VaultError;
+
+// token-2022 mints can take a fee on every transfer, this is what actually lands on the other side
+// legacy spl mints unpack fine here too, they just don't have the extension
+pub fn net_amount(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(VaultError::Overflow)?,
+        Err(_) => 0
+    };
+
+    Ok(amount - fee)
+}
+
+// same as anchor_spl's transfer_checked, except it passes the remaining accounts on
+// so a mint with a transfer hook gets the extra accounts its hook program asks for
+pub fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds
+    )
+    .map_err(Into::into)
+}
+
+// token-2022 won't close an account that still has fees withheld in it,
+// harvesting moves them to the mint (anyone can do it, no signer needed)
+pub fn harvest_withheld<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    account: AccountInfo<'info>
+) -> Result<()> {
+    let withheld = {
+        let data = account.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        state
+            .get_extension::<TransferFeeAmount>()
+            .map(|fees| u64::from(fees.withheld_amount))
+            .unwrap_or(0)
+    };
+
+    if withheld > 0 {
+        let ix = harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[account.key])?;
+        invoke(&ix, &[mint, account, token_program])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1360 @@
+use anchor_lang::{prelude::*, InstructionData, system_program, solana_program::entrypoint::ProgramResult};
+use anchor_vault::error::VaultError;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    native_token::LAMPORTS_PER_SOL,
+    system_instruction,
+};
+
+// anchor's entrypoint wants the account infos to live as long as the accounts slice,
+// program-test hands us a shorter lifetime so we leak a copy (fine in tests)
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_vault::entry(program_id, accounts, data)
+}
+
+// runs the program natively inside an in-process bank, no validator or .so needed
+// the nft contexts only read metaplex accounts, so the metadata program just has to exist as an executable
+fn metadata_program_stub(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+async fn setup() -> (ProgramTestContext, Keypair) {
+    let mut program_test = ProgramTest::new("anchor_vault", anchor_vault::ID, processor!(process_instruction));
+    program_test.add_program("mpl_token_metadata", anchor_spl::metadata::ID, processor!(metadata_program_stub));
+    let mut context = program_test.start_with_context().await;
+
+    let owner = Keypair::new();
+    let fund = system_instruction::transfer(&context.payer.pubkey(), &owner.pubkey(), 10 * LAMPORTS_PER_SOL);
+    send(&mut context, &[fund], &[]).await.unwrap();
+
+    (context, owner)
+}
+
+async fn send(context: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) -> std::result::Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+
+    context.banks_client.process_transaction(tx).await
+}
+
+// most tests only need one vault per owner, id 0
+fn state_pda(owner: &Pubkey) -> Pubkey {
+    state_pda_with_id(owner, 0)
+}
+
+fn state_pda_with_id(owner: &Pubkey, vault_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"state", owner.as_ref(), &vault_id.to_le_bytes()], &anchor_vault::ID).0
+}
+
+fn creator_index_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"creator_index", owner.as_ref()], &anchor_vault::ID).0
+}
+
+fn vault_pda(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", state.as_ref()], &anchor_vault::ID).0
+}
+
+fn recovery_pda(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"recovery", state.as_ref()], &anchor_vault::ID).0
+}
+
+fn initialize_ix(owner: &Pubkey, unlock_ts: i64) -> Instruction {
+    initialize_ix_with_id(owner, 0, unlock_ts)
+}
+
+fn initialize_ix_with_id(owner: &Pubkey, vault_id: u64, unlock_ts: i64) -> Instruction {
+    let state = state_pda_with_id(owner, vault_id);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Initialize {
+            owner: *owner,
+            state,
+            creator_index: creator_index_pda(owner),
+            vault: vault_pda(&state),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::Initialize { vault_id, unlock_ts }.data(),
+    }
+}
+
+fn payment_accounts(owner: &Pubkey, state: Pubkey, vault: Pubkey) -> Vec<AccountMeta> {
+    anchor_vault::accounts::Payment {
+        owner: *owner,
+        state,
+        vault,
+        system_program: system_program::ID,
+    }.to_account_metas(None)
+}
+
+fn deposit_ix(owner: &Pubkey, lamports: u64) -> Instruction {
+    let state = state_pda(owner);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(owner, state, vault_pda(&state)),
+        data: anchor_vault::instruction::Deposit { lamports }.data(),
+    }
+}
+
+fn withdraw_ix(owner: &Pubkey, lamports: u64) -> Instruction {
+    let state = state_pda(owner);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(owner, state, vault_pda(&state)),
+        data: anchor_vault::instruction::Withdraw { lamports }.data(),
+    }
+}
+
+fn close_ix(owner: &Pubkey) -> Instruction {
+    let state = state_pda(owner);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Close {
+            owner: *owner,
+            state,
+            creator_index: creator_index_pda(owner),
+            vault: vault_pda(&state),
+            recovery: recovery_pda(&state),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::Close {}.data(),
+    }
+}
+
+fn assert_custom_error(result: std::result::Result<(), BanksClientError>, code: u32) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => assert_eq!(actual, code),
+        err => panic!("expected custom error {code}, got {err:?}"),
+    }
+}
+
+async fn balance(context: &mut ProgramTestContext, key: Pubkey) -> u64 {
+    context.banks_client.get_balance(key).await.unwrap()
+}
+
+// a token-2022 mint with a 1% transfer fee and `amount` minted to the holder's ata, returns the mint and that ata
+async fn create_fee_mint(context: &mut ProgramTestContext, holder: &Pubkey, amount: u64) -> (Pubkey, Pubkey) {
+    use anchor_spl::{
+        associated_token::get_associated_token_address_with_program_id,
+        token_2022::spl_token_2022::{
+            self,
+            extension::{ExtensionType, transfer_fee::instruction::initialize_transfer_fee_config},
+            state::Mint,
+        },
+    };
+
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let token_program = spl_token_2022::ID;
+    let holder_ata = get_associated_token_address_with_program_id(holder, &mint.pubkey(), &token_program);
+
+    // capped well above anything we move
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap().minimum_balance(space);
+
+    // the associated token program's create instruction, built by hand to avoid another dev dependency
+    let create_ata = Instruction {
+        program_id: anchor_spl::associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(holder_ata, false),
+            AccountMeta::new_readonly(*holder, false),
+            AccountMeta::new_readonly(mint.pubkey(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: vec![],
+    };
+
+    let instructions = [
+        system_instruction::create_account(&payer, &mint.pubkey(), rent, space as u64, &token_program),
+        initialize_transfer_fee_config(&token_program, &mint.pubkey(), None, None, 100, 1_000_000).unwrap(),
+        spl_token_2022::instruction::initialize_mint2(&token_program, &mint.pubkey(), &payer, None, 6).unwrap(),
+        create_ata,
+        spl_token_2022::instruction::mint_to(&token_program, &mint.pubkey(), &holder_ata, &payer, &[], amount).unwrap(),
+    ];
+    send(context, &instructions, &[&mint]).await.unwrap();
+
+    (mint.pubkey(), holder_ata)
+}
+
+#[tokio::test]
+async fn deposit_withdraw_and_close() {
+    let (mut context, owner) = setup().await;
+    let vault = vault_pda(&state_pda(&owner.pubkey()));
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, vault).await, LAMPORTS_PER_SOL);
+
+    send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL / 2)], &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, vault).await, LAMPORTS_PER_SOL / 2);
+
+    send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, vault).await, 0);
+    assert!(context.banks_client.get_account(state_pda(&owner.pubkey())).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn withdraw_with_wrong_signer() {
+    let (mut context, owner) = setup().await;
+    let attacker = Keypair::new();
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    // the attacker signs but points at the owner's state and vault
+    let state = state_pda(&owner.pubkey());
+    let ix = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(&attacker.pubkey(), state, vault_pda(&state)),
+        data: anchor_vault::instruction::Withdraw { lamports: LAMPORTS_PER_SOL }.data(),
+    };
+
+    // the state seeds don't depend on the signer, so it's has_one = owner that stops them
+    let result = send(&mut context, &[ix], &[&attacker]).await;
+    assert_custom_error(result, ErrorCode::ConstraintHasOne.into());
+}
+
+#[tokio::test]
+async fn withdraw_with_wrong_seed() {
+    let (mut context, owner) = setup().await;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    // the old [b"vault", owner] derivation is not the vault anymore
+    let wrong_vault = Pubkey::find_program_address(&[b"vault", owner.pubkey().as_ref()], &anchor_vault::ID).0;
+    let ix = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(&owner.pubkey(), state_pda(&owner.pubkey()), wrong_vault),
+        data: anchor_vault::instruction::Withdraw { lamports: LAMPORTS_PER_SOL }.data(),
+    };
+
+    let result = send(&mut context, &[ix], &[&owner]).await;
+    assert_custom_error(result, ErrorCode::ConstraintSeeds.into());
+}
+
+#[tokio::test]
+async fn withdraw_more_than_deposited() {
+    let (mut context, owner) = setup().await;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let result = send(&mut context, &[withdraw_ix(&owner.pubkey(), 2 * LAMPORTS_PER_SOL)], &[&owner]).await;
+    assert_custom_error(result, VaultError::InsufficientFunds.into());
+}
+
+#[tokio::test]
+async fn withdraw_below_rent_exemption() {
+    let (mut context, owner) = setup().await;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let result = send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL - 1)], &[&owner]).await;
+    assert_custom_error(result, VaultError::WouldBreakRentExemption.into());
+}
+
+#[tokio::test]
+async fn withdraw_before_unlock() {
+    let (mut context, owner) = setup().await;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), i64::MAX)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let result = send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await;
+    assert_custom_error(result, VaultError::VaultLocked.into());
+}
+
+#[tokio::test]
+async fn pool_shares_follow_the_pool_balance() {
+    let (mut context, alice) = setup().await;
+    let bob = Keypair::new();
+    let fund = system_instruction::transfer(&context.payer.pubkey(), &bob.pubkey(), 10 * LAMPORTS_PER_SOL);
+    send(&mut context, &[fund], &[]).await.unwrap();
+
+    let seed = 7u64;
+    let pool = Pubkey::find_program_address(&[b"pool", alice.pubkey().as_ref(), &seed.to_le_bytes()], &anchor_vault::ID).0;
+    let vault = vault_pda(&pool);
+    let position = |depositor: Pubkey| Pubkey::find_program_address(&[b"position", pool.as_ref(), depositor.as_ref()], &anchor_vault::ID).0;
+
+    let create = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CreatePool {
+            creator: alice.pubkey(),
+            pool,
+            vault,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CreatePool { seed }.data(),
+    };
+    send(&mut context, &[create], &[&alice]).await.unwrap();
+
+    let deposit = |depositor: Pubkey, lamports: u64| Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::PoolDeposit {
+            depositor,
+            pool,
+            position: position(depositor),
+            vault,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::PoolDeposit { lamports }.data(),
+    };
+    send(&mut context, &[deposit(alice.pubkey(), LAMPORTS_PER_SOL)], &[&alice]).await.unwrap();
+    send(&mut context, &[deposit(bob.pubkey(), 2 * LAMPORTS_PER_SOL)], &[&bob]).await.unwrap();
+
+    // the pool earns 3 SOL, alice owns a third of it
+    let donation = system_instruction::transfer(&context.payer.pubkey(), &vault, 3 * LAMPORTS_PER_SOL);
+    send(&mut context, &[donation], &[]).await.unwrap();
+
+    let account = context.banks_client.get_account(position(alice.pubkey())).await.unwrap().unwrap();
+    let alice_position = anchor_vault::state::Position::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(alice_position.shares, LAMPORTS_PER_SOL);
+
+    let alice_before = balance(&mut context, alice.pubkey()).await;
+    let withdraw = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::PoolWithdraw {
+            depositor: alice.pubkey(),
+            pool,
+            position: position(alice.pubkey()),
+            vault,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::PoolWithdraw { shares: alice_position.shares }.data(),
+    };
+    send(&mut context, &[withdraw], &[&alice]).await.unwrap();
+
+    assert_eq!(balance(&mut context, alice.pubkey()).await - alice_before, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(balance(&mut context, vault).await, 4 * LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn withdraw_while_frozen() {
+    let (mut context, owner) = setup().await;
+    let guardian = Keypair::new();
+    let state = state_pda(&owner.pubkey());
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let set_guardian = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetGuardian { owner: owner.pubkey(), state }.to_account_metas(None),
+        data: anchor_vault::instruction::ProposeGuardian { guardian: Some(guardian.pubkey()) }.data(),
+    };
+    send(&mut context, &[set_guardian], &[&owner]).await.unwrap();
+
+    let freeze = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Freeze { guardian: guardian.pubkey(), state }.to_account_metas(None),
+        data: anchor_vault::instruction::Freeze {}.data(),
+    };
+    send(&mut context, &[freeze], &[&guardian]).await.unwrap();
+
+    let result = send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await;
+    assert_custom_error(result, VaultError::VaultFrozen.into());
+}
+
+fn transfer_ownership_ix(owner: &Pubkey, new_owner: &Pubkey) -> Instruction {
+    let state = state_pda(owner);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::TransferOwnership {
+            owner: *owner,
+            new_owner: *new_owner,
+            state,
+            recovery: recovery_pda(&state),
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::TransferOwnership {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn transfer_ownership_keeps_the_vault() {
+    let (mut context, owner) = setup().await;
+    let new_owner = Keypair::new();
+    let state = state_pda(&owner.pubkey());
+    let vault = vault_pda(&state);
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    send(&mut context, &[transfer_ownership_ix(&owner.pubkey(), &new_owner.pubkey())], &[&owner, &new_owner]).await.unwrap();
+
+    // the old owner is locked out of the same vault
+    let result = send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await;
+    assert_custom_error(result, ErrorCode::ConstraintHasOne.into());
+
+    let withdraw = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(&new_owner.pubkey(), state, vault),
+        data: anchor_vault::instruction::Withdraw { lamports: LAMPORTS_PER_SOL }.data(),
+    };
+    send(&mut context, &[withdraw], &[&new_owner]).await.unwrap();
+    assert_eq!(balance(&mut context, new_owner.pubkey()).await, LAMPORTS_PER_SOL);
+
+    // the vault stays in its creator's index, so closing it as the new owner clears it from there
+    let creator_index = creator_index_pda(&owner.pubkey());
+    let index_account = context.banks_client.get_account(creator_index).await.unwrap().unwrap();
+    let index = anchor_vault::state::CreatorIndex::try_deserialize(&mut index_account.data.as_slice()).unwrap();
+    assert_eq!(index.vault_ids, vec![0]);
+
+    let close = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Close {
+            owner: new_owner.pubkey(),
+            state,
+            creator_index,
+            vault,
+            recovery: recovery_pda(&state),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::Close {}.data(),
+    };
+    send(&mut context, &[close], &[&new_owner]).await.unwrap();
+    let index_account = context.banks_client.get_account(creator_index).await.unwrap().unwrap();
+    let index = anchor_vault::state::CreatorIndex::try_deserialize(&mut index_account.data.as_slice()).unwrap();
+    assert!(index.vault_ids.is_empty());
+}
+
+#[tokio::test]
+async fn named_vaults_are_independent() {
+    let (mut context, owner) = setup().await;
+    let savings = state_pda_with_id(&owner.pubkey(), 1);
+
+    // vault 0 has no lock, savings is locked for a day
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0), initialize_ix_with_id(&owner.pubkey(), 1, now + 86_400)], &[&owner]).await.unwrap();
+
+    let index_account = context.banks_client.get_account(creator_index_pda(&owner.pubkey())).await.unwrap().unwrap();
+    let index = anchor_vault::state::CreatorIndex::try_deserialize(&mut index_account.data.as_slice()).unwrap();
+    assert_eq!(index.vault_ids, vec![0, 1]);
+
+    let deposit_savings = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(&owner.pubkey(), savings, vault_pda(&savings)),
+        data: anchor_vault::instruction::Deposit { lamports: LAMPORTS_PER_SOL }.data(),
+    };
+    let withdraw_savings = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: payment_accounts(&owner.pubkey(), savings, vault_pda(&savings)),
+        data: anchor_vault::instruction::Withdraw { lamports: LAMPORTS_PER_SOL }.data(),
+    };
+    send(&mut context, &[deposit_ix(&owner.pubkey(), LAMPORTS_PER_SOL), deposit_savings], &[&owner]).await.unwrap();
+
+    // the lock on savings doesn't touch vault 0
+    send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+    let result = send(&mut context, &[withdraw_savings], &[&owner]).await;
+    assert_custom_error(result, VaultError::VaultLocked.into());
+
+    send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+    let index_account = context.banks_client.get_account(creator_index_pda(&owner.pubkey())).await.unwrap().unwrap();
+    let index = anchor_vault::state::CreatorIndex::try_deserialize(&mut index_account.data.as_slice()).unwrap();
+    assert_eq!(index.vault_ids, vec![1]);
+}
+
+#[tokio::test]
+async fn withdraw_over_the_cap() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), 2 * LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let set_cap = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetWithdrawCap { owner: owner.pubkey(), state }.to_account_metas(None),
+        data: anchor_vault::instruction::SetWithdrawCap { cap: LAMPORTS_PER_SOL }.data(),
+    };
+    send(&mut context, &[set_cap], &[&owner]).await.unwrap();
+
+    send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL / 2)], &[&owner]).await.unwrap();
+    let result = send(&mut context, &[withdraw_ix(&owner.pubkey(), LAMPORTS_PER_SOL / 2 + 1)], &[&owner]).await;
+    assert_custom_error(result, VaultError::WithdrawCapExceeded.into());
+}
+
+#[tokio::test]
+async fn batch_withdraw_pays_every_recipient() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let vault = vault_pda(&state);
+    let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), 2 * LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let mut accounts = anchor_vault::accounts::BatchWithdraw {
+        owner: owner.pubkey(),
+        state,
+        vault,
+        system_program: system_program::ID,
+    }.to_account_metas(None);
+    accounts.extend(recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+
+    let batch = Instruction {
+        program_id: anchor_vault::ID,
+        accounts,
+        data: anchor_vault::instruction::BatchWithdraw { amounts: vec![LAMPORTS_PER_SOL / 2, LAMPORTS_PER_SOL / 4] }.data(),
+    };
+    send(&mut context, &[batch], &[&owner]).await.unwrap();
+
+    assert_eq!(balance(&mut context, recipients[0]).await, LAMPORTS_PER_SOL / 2);
+    assert_eq!(balance(&mut context, recipients[1]).await, LAMPORTS_PER_SOL / 4);
+    assert_eq!(balance(&mut context, vault).await, 2 * LAMPORTS_PER_SOL - 3 * LAMPORTS_PER_SOL / 4);
+}
+
+#[tokio::test]
+async fn stream_pays_what_has_accrued() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let vault = vault_pda(&state);
+    let recipient = Pubkey::new_unique();
+    let seed = 0u64;
+    let stream = Pubkey::find_program_address(&[b"stream", state.as_ref(), recipient.as_ref(), &seed.to_le_bytes()], &anchor_vault::ID).0;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    send(&mut context, &[deposit_ix(&owner.pubkey(), 2 * LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    // already finished so the accrued amount doesn't depend on the bank clock moving
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let rate = 10_000_000;
+    let create = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CreateStream {
+            owner: owner.pubkey(),
+            recipient,
+            state,
+            stream,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CreateStream { seed, rate, start_ts: now - 100, end_ts: now - 10 }.data(),
+    };
+    send(&mut context, &[create], &[&owner]).await.unwrap();
+
+    // anyone can crank it, here the test payer does
+    let crank = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::WithdrawStream {
+            recipient,
+            state,
+            stream,
+            vault,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::WithdrawStream {}.data(),
+    };
+    send(&mut context, std::slice::from_ref(&crank), &[]).await.unwrap();
+    assert_eq!(balance(&mut context, recipient).await, 90 * rate);
+
+    // same instruction and signer, so it needs a new blockhash or the bank treats it as a duplicate
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    context.banks_client.get_new_latest_blockhash(&blockhash).await.unwrap();
+    let result = send(&mut context, &[crank], &[]).await;
+    assert_custom_error(result, VaultError::NothingToClaim.into());
+}
+
+#[tokio::test]
+async fn token_2022_deposit_credits_net_of_transfer_fee() {
+    use anchor_spl::token_2022::spl_token_2022::{self, extension::StateWithExtensions, state::Account as TokenAccount};
+
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let token_program = spl_token_2022::ID;
+    let (mint, owner_ata) = create_fee_mint(&mut context, &owner.pubkey(), 10_000).await;
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    let vault_ata = Pubkey::find_program_address(&[b"vault", state.as_ref(), mint.as_ref()], &anchor_vault::ID).0;
+    let spl_ix = |data: Vec<u8>| Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SplPayment {
+            owner: owner.pubkey(),
+            mint,
+            owner_ata,
+            state,
+            vault_ata,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data,
+    };
+
+    let token_amount = |data: Vec<u8>| StateWithExtensions::<TokenAccount>::unpack(&data).unwrap().base.amount;
+
+    send(&mut context, &[spl_ix(anchor_vault::instruction::DepositSpl { amount: 10_000 }.data())], &[&owner]).await.unwrap();
+    let vault_account = context.banks_client.get_account(vault_ata).await.unwrap().unwrap();
+    assert_eq!(token_amount(vault_account.data), 9_900);
+
+    // closing the state would strand the tokens
+    let result = send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await;
+    assert_custom_error(result, VaultError::OpenAccounts.into());
+
+    // the fee comes out again on the way back
+    send(&mut context, &[spl_ix(anchor_vault::instruction::WithdrawSpl { amount: 9_900 }.data())], &[&owner]).await.unwrap();
+    let owner_account = context.banks_client.get_account(owner_ata).await.unwrap().unwrap();
+    assert_eq!(token_amount(owner_account.data), 9_801);
+
+    // withdrawing everything closes the token vault, withheld fees and all, so the state can go
+    assert!(context.banks_client.get_account(vault_ata).await.unwrap().is_none());
+    send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+}
+
+#[tokio::test]
+async fn token_2022_vesting_vests_net_of_transfer_fee() {
+    use anchor_spl::{
+        associated_token::get_associated_token_address_with_program_id,
+        token_2022::spl_token_2022::{self, extension::StateWithExtensions, state::Account as TokenAccount},
+    };
+
+    let (mut context, funder) = setup().await;
+    let beneficiary = Keypair::new();
+    let fund = system_instruction::transfer(&context.payer.pubkey(), &beneficiary.pubkey(), LAMPORTS_PER_SOL);
+    send(&mut context, &[fund], &[]).await.unwrap();
+
+    let token_program = spl_token_2022::ID;
+    let (mint, funder_ata) = create_fee_mint(&mut context, &funder.pubkey(), 10_000).await;
+
+    let seed = 0u64;
+    let vesting = Pubkey::find_program_address(&[b"vesting", funder.pubkey().as_ref(), beneficiary.pubkey().as_ref(), &seed.to_le_bytes()], &anchor_vault::ID).0;
+    let vault_ata = Pubkey::find_program_address(&[b"vault", vesting.as_ref(), mint.as_ref()], &anchor_vault::ID).0;
+
+    // fully vested already, the one claim takes everything and closes the vault
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let create_vesting = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CreateVestingSpl {
+            funder: funder.pubkey(),
+            beneficiary: beneficiary.pubkey(),
+            mint,
+            funder_ata,
+            vesting,
+            vault_ata,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CreateVestingSpl { seed, amount: 10_000, start_ts: now - 100, cliff_ts: now - 100, end_ts: now - 10 }.data(),
+    };
+    send(&mut context, &[create_vesting], &[&funder]).await.unwrap();
+
+    // only what arrived after the fee is vested
+    let vesting_account = context.banks_client.get_account(vesting).await.unwrap().unwrap();
+    let state = anchor_vault::state::Vesting::try_deserialize(&mut vesting_account.data.as_slice()).unwrap();
+    assert_eq!(state.total_amount, 9_900);
+
+    let beneficiary_ata = get_associated_token_address_with_program_id(&beneficiary.pubkey(), &mint, &token_program);
+    let claim = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ClaimSpl {
+            beneficiary: beneficiary.pubkey(),
+            funder: funder.pubkey(),
+            mint,
+            beneficiary_ata,
+            vesting,
+            vault_ata,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::ClaimSpl {}.data(),
+    };
+    send(&mut context, &[claim], &[&beneficiary]).await.unwrap();
+
+    // the fee comes out again on the claim, and the withheld fees had to be harvested for the vault to close
+    let beneficiary_account = context.banks_client.get_account(beneficiary_ata).await.unwrap().unwrap();
+    assert_eq!(StateWithExtensions::<TokenAccount>::unpack(&beneficiary_account.data).unwrap().base.amount, 9_801);
+    assert!(context.banks_client.get_account(vault_ata).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(vesting).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn final_spl_claim_sweeps_stray_tokens() {
+    use anchor_spl::{associated_token::get_associated_token_address, token::spl_token::{self, state::{Account as TokenAccount, Mint}}};
+    use solana_sdk::program_pack::Pack;
+
+    let (mut context, funder) = setup().await;
+    let beneficiary = Keypair::new();
+    let fund = system_instruction::transfer(&context.payer.pubkey(), &beneficiary.pubkey(), LAMPORTS_PER_SOL);
+    send(&mut context, &[fund], &[]).await.unwrap();
+
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap().minimum_balance(Mint::LEN);
+    let create_mint = [
+        system_instruction::create_account(&context.payer.pubkey(), &mint.pubkey(), rent, Mint::LEN as u64, &spl_token::ID),
+        spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &funder.pubkey(), None, 0).unwrap(),
+    ];
+    send(&mut context, &create_mint, &[&mint]).await.unwrap();
+
+    let funder_ata = get_associated_token_address(&funder.pubkey(), &mint.pubkey());
+    let create_ata = Instruction {
+        program_id: anchor_spl::associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(context.payer.pubkey(), true),
+            AccountMeta::new(funder_ata, false),
+            AccountMeta::new_readonly(funder.pubkey(), false),
+            AccountMeta::new_readonly(mint.pubkey(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+        data: vec![],
+    };
+    let mint_to = spl_token::instruction::mint_to(&spl_token::ID, &mint.pubkey(), &funder_ata, &funder.pubkey(), &[], 1_000).unwrap();
+    send(&mut context, &[create_ata, mint_to], &[&funder]).await.unwrap();
+
+    let seed = 0u64;
+    let vesting = Pubkey::find_program_address(&[b"vesting", funder.pubkey().as_ref(), beneficiary.pubkey().as_ref(), &seed.to_le_bytes()], &anchor_vault::ID).0;
+    let vault_ata = Pubkey::find_program_address(&[b"vault", vesting.as_ref(), mint.pubkey().as_ref()], &anchor_vault::ID).0;
+
+    // already fully vested so the first claim is the last one
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let create_vesting = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CreateVestingSpl {
+            funder: funder.pubkey(),
+            beneficiary: beneficiary.pubkey(),
+            mint: mint.pubkey(),
+            funder_ata,
+            vesting,
+            vault_ata,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CreateVestingSpl { seed, amount: 900, start_ts: now - 100, cliff_ts: now - 100, end_ts: now - 10 }.data(),
+    };
+    send(&mut context, &[create_vesting], &[&funder]).await.unwrap();
+
+    // anyone can send tokens to the vault, that used to make the closing claim fail
+    let stray = spl_token::instruction::transfer(&spl_token::ID, &funder_ata, &vault_ata, &funder.pubkey(), &[], 1).unwrap();
+    send(&mut context, &[stray], &[&funder]).await.unwrap();
+
+    let beneficiary_ata = get_associated_token_address(&beneficiary.pubkey(), &mint.pubkey());
+    let claim = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ClaimSpl {
+            beneficiary: beneficiary.pubkey(),
+            funder: funder.pubkey(),
+            mint: mint.pubkey(),
+            beneficiary_ata,
+            vesting,
+            vault_ata,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::ClaimSpl {}.data(),
+    };
+    send(&mut context, &[claim], &[&beneficiary]).await.unwrap();
+
+    let beneficiary_account = context.banks_client.get_account(beneficiary_ata).await.unwrap().unwrap();
+    assert_eq!(TokenAccount::unpack(&beneficiary_account.data).unwrap().amount, 901);
+    assert!(context.banks_client.get_account(vault_ata).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(vesting).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn close_waits_for_open_delegations() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let delegate = Pubkey::new_unique();
+    let delegation = Pubkey::find_program_address(&[b"delegate", state.as_ref(), delegate.as_ref()], &anchor_vault::ID).0;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    let approve = |allowance: u64| Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ApproveDelegate {
+            owner: owner.pubkey(),
+            delegate,
+            state,
+            delegation,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::ApproveDelegate { allowance, period: 0 }.data(),
+    };
+    // approving the same delegate twice still counts as one open delegation
+    send(&mut context, &[approve(LAMPORTS_PER_SOL), approve(2 * LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    // otherwise a vault initialized again with the same id would honour the old allowance
+    let result = send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await;
+    assert_custom_error(result, VaultError::OpenAccounts.into());
+
+    let revoke = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::RevokeDelegate {
+            owner: owner.pubkey(),
+            delegate,
+            state,
+            delegation,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::RevokeDelegate {}.data(),
+    };
+    send(&mut context, &[revoke, close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+    assert!(context.banks_client.get_account(state).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn delegate_stake_to_a_prefunded_stake_address() {
+    #[allow(deprecated)]
+    use anchor_lang::solana_program::{stake::{self, config as stake_config}, sysvar, vote};
+
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let vault = vault_pda(&state);
+    let stake_account = Pubkey::find_program_address(&[b"stake", state.as_ref()], &anchor_vault::ID).0;
+    let vote_account = *context.genesis_config().accounts.iter()
+        .find(|(_, account)| account.owner == vote::program::ID)
+        .unwrap().0;
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0), deposit_ix(&owner.pubkey(), 3 * LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    // anyone can send lamports to the stake address, that used to make create_account fail forever
+    let gift = context.banks_client.get_rent().await.unwrap().minimum_balance(0);
+    let grief = system_instruction::transfer(&context.payer.pubkey(), &stake_account, gift);
+    send(&mut context, &[grief], &[]).await.unwrap();
+
+    #[allow(deprecated)]
+    let delegate = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::DelegateStake {
+            owner: owner.pubkey(),
+            state,
+            vault,
+            stake_account,
+            vote_account,
+            stake_config: stake_config::ID,
+            clock: sysvar::clock::ID,
+            rent: sysvar::rent::ID,
+            stake_history: sysvar::stake_history::ID,
+            stake_program: stake::program::ID,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::DelegateStake { lamports: 2 * LAMPORTS_PER_SOL }.data(),
+    };
+    send(&mut context, &[delegate], &[&owner]).await.unwrap();
+
+    let stake = context.banks_client.get_account(stake_account).await.unwrap().unwrap();
+    assert_eq!(stake.owner, stake::program::ID);
+    assert_eq!(stake.lamports, 2 * LAMPORTS_PER_SOL + gift);
+    assert_eq!(balance(&mut context, vault).await, LAMPORTS_PER_SOL);
+}
+
+fn set_recovery_ix(owner: &Pubkey, recovery_keys: Vec<Pubkey>, threshold: u8) -> Instruction {
+    let state = state_pda(owner);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetRecovery {
+            owner: *owner,
+            state,
+            recovery: recovery_pda(&state),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::SetRecovery { recovery_keys, threshold, delay: 0 }.data(),
+    }
+}
+
+fn recover_ix(recovery_key: &Pubkey, state: Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Recover {
+            recovery_key: *recovery_key,
+            state,
+            recovery: recovery_pda(&state),
+        }.to_account_metas(None),
+        data,
+    }
+}
+
+#[tokio::test]
+async fn stale_recovery_can_be_replaced() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let [bad_key, key_1, key_2] = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let new_owner = Pubkey::new_unique();
+
+    send(&mut context, &[
+        initialize_ix(&owner.pubkey(), 0),
+        set_recovery_ix(&owner.pubkey(), vec![bad_key.pubkey(), key_1.pubkey(), key_2.pubkey()], 2),
+    ], &[&owner]).await.unwrap();
+
+    // a bad key starts a recovery the others won't approve
+    let start = |key: &Keypair, new_owner: Pubkey| recover_ix(&key.pubkey(), state, anchor_vault::instruction::StartRecovery { new_owner }.data());
+    send(&mut context, &[start(&bad_key, bad_key.pubkey())], &[&bad_key]).await.unwrap();
+    let result = send(&mut context, &[start(&key_1, new_owner)], &[&key_1]).await;
+    assert_custom_error(result, VaultError::RecoveryPending.into());
+
+    // once its approval window has passed it doesn't block the honest keys anymore
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += anchor_vault::state::RECOVERY_APPROVAL_WINDOW;
+    context.set_sysvar(&clock);
+
+    send(&mut context, &[
+        start(&key_1, new_owner),
+        recover_ix(&key_2.pubkey(), state, anchor_vault::instruction::ApproveRecovery {}.data()),
+        recover_ix(&key_2.pubkey(), state, anchor_vault::instruction::CompleteRecovery {}.data()),
+    ], &[&key_1, &key_2]).await.unwrap();
+
+    let state_account = context.banks_client.get_account(state).await.unwrap().unwrap();
+    let vault_state = anchor_vault::state::VaultState::try_deserialize(&mut state_account.data.as_slice()).unwrap();
+    assert_eq!(vault_state.owner, new_owner);
+}
+
+#[tokio::test]
+async fn transfer_ownership_drops_a_pending_recovery() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let recovery_key = Keypair::new();
+    let new_owner = Keypair::new();
+
+    send(&mut context, &[
+        initialize_ix(&owner.pubkey(), 0),
+        set_recovery_ix(&owner.pubkey(), vec![recovery_key.pubkey()], 1),
+    ], &[&owner]).await.unwrap();
+
+    // fully approved with no delay, it could be completed right away
+    let start = recover_ix(&recovery_key.pubkey(), state, anchor_vault::instruction::StartRecovery { new_owner: recovery_key.pubkey() }.data());
+    send(&mut context, &[start], &[&recovery_key]).await.unwrap();
+
+    send(&mut context, &[transfer_ownership_ix(&owner.pubkey(), &new_owner.pubkey())], &[&owner, &new_owner]).await.unwrap();
+    assert!(context.banks_client.get_account(recovery_pda(&state)).await.unwrap().is_none());
+
+    let complete = recover_ix(&recovery_key.pubkey(), state, anchor_vault::instruction::CompleteRecovery {}.data());
+    let result = send(&mut context, &[complete], &[&recovery_key]).await;
+    assert_custom_error(result, ErrorCode::AccountNotInitialized.into());
+
+    let state_account = context.banks_client.get_account(state).await.unwrap().unwrap();
+    let vault_state = anchor_vault::state::VaultState::try_deserialize(&mut state_account.data.as_slice()).unwrap();
+    assert_eq!(vault_state.owner, new_owner.pubkey());
+}
+
+#[tokio::test]
+async fn close_removes_the_recovery_keys() {
+    let (mut context, owner) = setup().await;
+    let state = state_pda(&owner.pubkey());
+    let recovery_key = Keypair::new();
+
+    send(&mut context, &[
+        initialize_ix(&owner.pubkey(), 0),
+        set_recovery_ix(&owner.pubkey(), vec![recovery_key.pubkey()], 1),
+    ], &[&owner]).await.unwrap();
+    send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+    assert!(context.banks_client.get_account(recovery_pda(&state)).await.unwrap().is_none());
+
+    // the same id gets the same state pda, the old recovery key has nothing to act on anymore
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+    let start = recover_ix(&recovery_key.pubkey(), state, anchor_vault::instruction::StartRecovery { new_owner: recovery_key.pubkey() }.data());
+    let result = send(&mut context, &[start], &[&recovery_key]).await;
+    assert_custom_error(result, ErrorCode::AccountNotInitialized.into());
+}
+
+#[tokio::test]
+async fn cancel_stream_while_frozen_stops_it() {
+    let (mut context, owner) = setup().await;
+    let guardian = Keypair::new();
+    let state = state_pda(&owner.pubkey());
+    let vault = vault_pda(&state);
+    let recipient = Pubkey::new_unique();
+    let stream_pda = |seed: u64| Pubkey::find_program_address(&[b"stream", state.as_ref(), recipient.as_ref(), &seed.to_le_bytes()], &anchor_vault::ID).0;
+    let stream = stream_pda(0);
+
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0), deposit_ix(&owner.pubkey(), 5 * LAMPORTS_PER_SOL)], &[&owner]).await.unwrap();
+
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let rate = 10_000_000;
+    let create = |seed: u64, rate: u64, start_ts: i64, end_ts: i64| Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CreateStream {
+            owner: owner.pubkey(),
+            recipient,
+            state,
+            stream: stream_pda(seed),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CreateStream { seed, rate, start_ts, end_ts }.data(),
+    };
+
+    // a stream whose total doesn't fit in a u64 could never be withdrawn or cancelled
+    let result = send(&mut context, &[create(1, u64::MAX, now, now + 2)], &[&owner]).await;
+    assert_custom_error(result, VaultError::Overflow.into());
+
+    send(&mut context, &[create(0, rate, now - 100, now + 1_000)], &[&owner]).await.unwrap();
+    let result = send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await;
+    assert_custom_error(result, VaultError::OpenAccounts.into());
+
+    let set_guardian = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetGuardian { owner: owner.pubkey(), state }.to_account_metas(None),
+        data: anchor_vault::instruction::ProposeGuardian { guardian: Some(guardian.pubkey()) }.data(),
+    };
+    send(&mut context, &[set_guardian], &[&owner]).await.unwrap();
+    let freeze = |data: Vec<u8>| Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Freeze { guardian: guardian.pubkey(), state }.to_account_metas(None),
+        data,
+    };
+    send(&mut context, &[freeze(anchor_vault::instruction::Freeze {}.data())], &[&guardian]).await.unwrap();
+
+    let cancel = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CancelStream {
+            owner: owner.pubkey(),
+            recipient,
+            state,
+            stream,
+            vault,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CancelStream {}.data(),
+    };
+    // the frozen vault can't pay, so the stream stops accruing but stays open for what's owed
+    send(&mut context, std::slice::from_ref(&cancel), &[&owner]).await.unwrap();
+    let stream_account = context.banks_client.get_account(stream).await.unwrap().unwrap();
+    let stopped = anchor_vault::state::Stream::try_deserialize(&mut stream_account.data.as_slice()).unwrap();
+    assert!(stopped.end_ts < now + 1_000);
+
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += 500;
+    context.set_sysvar(&clock);
+
+    let crank = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::WithdrawStream {
+            recipient,
+            state,
+            stream,
+            vault,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::WithdrawStream {}.data(),
+    };
+    send(&mut context, &[freeze(anchor_vault::instruction::Unfreeze {}.data()), crank], &[&guardian]).await.unwrap();
+    assert_eq!(balance(&mut context, recipient).await, rate * (stopped.end_ts - stopped.start_ts) as u64);
+
+    // nothing is owed anymore, cancelling again closes it and the vault can be closed
+    send(&mut context, &[cancel, close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+    assert!(context.banks_client.get_account(stream).await.unwrap().is_none());
+}
+
+fn multisig_pda(creator: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"multisig", creator.as_ref(), &seed.to_le_bytes()], &anchor_vault::ID).0
+}
+
+fn proposal_pda(multisig: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"proposal", multisig.as_ref(), &index.to_le_bytes()], &anchor_vault::ID).0
+}
+
+fn propose_ix(proposer: &Pubkey, multisig: Pubkey, index: u64, recipient: Pubkey, lamports: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ProposeWithdraw {
+            proposer: *proposer,
+            multisig,
+            proposal: proposal_pda(&multisig, index),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::ProposeWithdraw { recipient, lamports }.data(),
+    }
+}
+
+fn approve_ix(owner: &Pubkey, multisig: Pubkey, index: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ApproveWithdraw {
+            owner: *owner,
+            multisig,
+            proposal: proposal_pda(&multisig, index),
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::ApproveWithdraw {}.data(),
+    }
+}
+
+fn execute_ix(executor: &Pubkey, proposer: &Pubkey, recipient: &Pubkey, multisig: Pubkey, index: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ExecuteWithdraw {
+            executor: *executor,
+            proposer: *proposer,
+            recipient: *recipient,
+            multisig,
+            proposal: proposal_pda(&multisig, index),
+            vault: vault_pda(&multisig),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::ExecuteWithdraw {}.data(),
+    }
+}
+
+// three funded owners and a 2 of 3 multisig with 2 SOL in its vault
+async fn setup_multisig(context: &mut ProgramTestContext, creator: &Keypair) -> (Pubkey, [Keypair; 3]) {
+    let owners = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let multisig = multisig_pda(&creator.pubkey(), 0);
+
+    let create = Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CreateMultisig {
+            creator: creator.pubkey(),
+            multisig,
+            vault: vault_pda(&multisig),
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CreateMultisig { seed: 0, owners: owners.iter().map(|owner| owner.pubkey()).collect(), threshold: 2 }.data(),
+    };
+    let mut instructions = vec![create, system_instruction::transfer(&context.payer.pubkey(), &vault_pda(&multisig), 2 * LAMPORTS_PER_SOL)];
+    instructions.extend(owners.iter().map(|owner| system_instruction::transfer(&context.payer.pubkey(), &owner.pubkey(), LAMPORTS_PER_SOL)));
+    send(context, &instructions, &[creator]).await.unwrap();
+
+    (multisig, owners)
+}
+
+#[tokio::test]
+async fn multisig_withdraw_needs_the_threshold() {
+    let (mut context, creator) = setup().await;
+    let (multisig, [owner_1, owner_2, _]) = setup_multisig(&mut context, &creator).await;
+    let recipient = Pubkey::new_unique();
+    let executor = context.payer.pubkey();
+
+    // the creator isn't one of the owners
+    let result = send(&mut context, &[propose_ix(&creator.pubkey(), multisig, 0, recipient, LAMPORTS_PER_SOL)], &[&creator]).await;
+    assert_custom_error(result, VaultError::NotAnOwner.into());
+
+    send(&mut context, &[propose_ix(&owner_1.pubkey(), multisig, 0, recipient, LAMPORTS_PER_SOL)], &[&owner_1]).await.unwrap();
+
+    let result = send(&mut context, &[approve_ix(&creator.pubkey(), multisig, 0)], &[&creator]).await;
+    assert_custom_error(result, VaultError::NotAnOwner.into());
+
+    // proposing already counted as owner_1's approval
+    let result = send(&mut context, &[approve_ix(&owner_1.pubkey(), multisig, 0)], &[&owner_1]).await;
+    assert_custom_error(result, VaultError::AlreadyApproved.into());
+
+    let result = send(&mut context, &[execute_ix(&executor, &owner_1.pubkey(), &recipient, multisig, 0)], &[]).await;
+    assert_custom_error(result, VaultError::NotEnoughApprovals.into());
+
+    // 2 of 3 is enough, and anyone can execute from there
+    send(&mut context, &[approve_ix(&owner_2.pubkey(), multisig, 0)], &[&owner_2]).await.unwrap();
+    send(&mut context, &[execute_ix(&executor, &owner_1.pubkey(), &recipient, multisig, 0)], &[]).await.unwrap();
+    assert_eq!(balance(&mut context, recipient).await, LAMPORTS_PER_SOL);
+    assert!(context.banks_client.get_account(proposal_pda(&multisig, 0)).await.unwrap().is_none());
+
+    // the proposal is closed by the first execute, so it can't pay out twice
+    let result = send(&mut context, &[execute_ix(&executor, &owner_1.pubkey(), &recipient, multisig, 0)], &[]).await;
+    assert_custom_error(result, ErrorCode::AccountNotInitialized.into());
+    assert_eq!(balance(&mut context, recipient).await, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn proposer_can_cancel_a_proposal() {
+    let (mut context, creator) = setup().await;
+    let (multisig, [owner_1, owner_2, owner_3]) = setup_multisig(&mut context, &creator).await;
+    let recipient = Pubkey::new_unique();
+
+    send(&mut context, &[propose_ix(&owner_1.pubkey(), multisig, 0, recipient, LAMPORTS_PER_SOL)], &[&owner_1]).await.unwrap();
+
+    let cancel_ix = |proposer: &Pubkey| Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CancelWithdraw {
+            proposer: *proposer,
+            multisig,
+            proposal: proposal_pda(&multisig, 0),
+        }.to_account_metas(None),
+        data: anchor_vault::instruction::CancelWithdraw {}.data(),
+    };
+
+    // another owner can't cancel it for them
+    let result = send(&mut context, &[cancel_ix(&owner_2.pubkey())], &[&owner_2]).await;
+    assert_custom_error(result, ErrorCode::ConstraintHasOne.into());
+
+    let before = balance(&mut context, owner_1.pubkey()).await;
+    let rent = balance(&mut context, proposal_pda(&multisig, 0)).await;
+    send(&mut context, &[cancel_ix(&owner_1.pubkey())], &[&owner_1]).await.unwrap();
+    assert_eq!(balance(&mut context, owner_1.pubkey()).await, before + rent);
+
+    // a cancelled proposal can't pick up the approval it was missing
+    let result = send(&mut context, &[approve_ix(&owner_3.pubkey(), multisig, 0)], &[&owner_3]).await;
+    assert_custom_error(result, ErrorCode::AccountNotInitialized.into());
+}
+
+// a legacy spl mint held by the owner, with metaplex's metadata (and master edition) written straight into the bank
+async fn create_nft(context: &mut ProgramTestContext, owner: &Keypair, supply: u64, decimals: u8, master_edition: bool) -> Pubkey {
+    use anchor_spl::{
+        associated_token::get_associated_token_address,
+        metadata::mpl_token_metadata::{accounts::MasterEdition, types::Key},
+        token::spl_token::{self, state::Mint},
+    };
+    use solana_sdk::{account::Account, program_pack::Pack};
+
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap().minimum_balance(Mint::LEN);
+    let owner_ata = get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+    let create_ata = Instruction {
+        program_id: anchor_spl::associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(context.payer.pubkey(), true),
+            AccountMeta::new(owner_ata, false),
+            AccountMeta::new_readonly(owner.pubkey(), false),
+            AccountMeta::new_readonly(mint.pubkey(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+        data: vec![],
+    };
+    let instructions = [
+        system_instruction::create_account(&context.payer.pubkey(), &mint.pubkey(), rent, Mint::LEN as u64, &spl_token::ID),
+        spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &owner.pubkey(), None, decimals).unwrap(),
+        create_ata,
+        spl_token::instruction::mint_to(&spl_token::ID, &mint.pubkey(), &owner_ata, &owner.pubkey(), &[], supply).unwrap(),
+    ];
+    send(context, &instructions, &[&mint, owner]).await.unwrap();
+
+    set_metadata(context, &mint.pubkey(), None);
+
+    if master_edition {
+        let edition = MasterEdition { key: Key::MasterEditionV2, supply: 0, max_supply: Some(0) };
+        let account = Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: edition.try_to_vec().unwrap(),
+            owner: anchor_spl::metadata::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        context.set_account(&MasterEdition::find_pda(&mint.pubkey()).0, &account.into());
+    }
+
+    mint.pubkey()
+}
+
+// overwrites the nft's metadata, the collection is (collection mint, verified)
+fn set_metadata(context: &mut ProgramTestContext, mint: &Pubkey, collection: Option<(Pubkey, bool)>) {
+    use anchor_spl::metadata::mpl_token_metadata::{accounts::Metadata, types::{Collection, Key, TokenStandard}};
+    use solana_sdk::account::Account;
+
+    let metadata = Metadata {
+        key: Key::MetadataV1,
+        update_authority: Pubkey::new_unique(),
+        mint: *mint,
+        name: "Vault Test".to_string(),
+        symbol: "VT".to_string(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: collection.map(|(key, verified)| Collection { verified, key }),
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    };
+    let account = Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: metadata.try_to_vec().unwrap(),
+        owner: anchor_spl::metadata::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&Metadata::find_pda(mint).0, &account.into());
+}
+
+fn nft_payment_ix(owner: &Pubkey, mint: Pubkey, collection_mint: Option<Pubkey>, data: Vec<u8>) -> Instruction {
+    use anchor_spl::{associated_token::get_associated_token_address, metadata::mpl_token_metadata::accounts::{MasterEdition, Metadata}, token::spl_token};
+
+    let state = state_pda(owner);
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::NftPayment {
+            owner: *owner,
+            mint,
+            owner_ata: get_associated_token_address(owner, &mint),
+            state,
+            vault_ata: Pubkey::find_program_address(&[b"vault", state.as_ref(), mint.as_ref()], &anchor_vault::ID).0,
+            metadata: Metadata::find_pda(&mint).0,
+            master_edition: MasterEdition::find_pda(&mint).0,
+            collection_mint,
+            metadata_program: anchor_spl::metadata::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }.to_account_metas(None),
+        data,
+    }
+}
+
+#[tokio::test]
+async fn nft_deposit_and_withdraw_in_a_collection() {
+    let (mut context, owner) = setup().await;
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    let collection_mint = create_nft(&mut context, &owner, 1, 0, true).await;
+    let mint = create_nft(&mut context, &owner, 1, 0, true).await;
+    set_metadata(&mut context, &mint, Some((collection_mint, true)));
+
+    let deposit = nft_payment_ix(&owner.pubkey(), mint, Some(collection_mint), anchor_vault::instruction::DepositNft {}.data());
+    send(&mut context, &[deposit], &[&owner]).await.unwrap();
+
+    // the vault holds the nft, so the state can't be closed under it
+    let result = send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await;
+    assert_custom_error(result, VaultError::OpenAccounts.into());
+
+    let withdraw = nft_payment_ix(&owner.pubkey(), mint, None, anchor_vault::instruction::WithdrawNft {}.data());
+    send(&mut context, &[withdraw], &[&owner]).await.unwrap();
+    send(&mut context, &[close_ix(&owner.pubkey())], &[&owner]).await.unwrap();
+}
+
+#[tokio::test]
+async fn deposit_nft_rejects_fungible_mints() {
+    let (mut context, owner) = setup().await;
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    // more than one token, or one base unit of a token with decimals, isn't an nft even with metaplex accounts
+    for (supply, decimals) in [(2, 0), (1, 6)] {
+        let mint = create_nft(&mut context, &owner, supply, decimals, true).await;
+        let deposit = nft_payment_ix(&owner.pubkey(), mint, None, anchor_vault::instruction::DepositNft {}.data());
+        let result = send(&mut context, &[deposit], &[&owner]).await;
+        assert_custom_error(result, VaultError::NotAnNft.into());
+    }
+}
+
+#[tokio::test]
+async fn deposit_nft_needs_a_master_edition() {
+    let (mut context, owner) = setup().await;
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    let mint = create_nft(&mut context, &owner, 1, 0, false).await;
+    let deposit = nft_payment_ix(&owner.pubkey(), mint, None, anchor_vault::instruction::DepositNft {}.data());
+    let result = send(&mut context, &[deposit], &[&owner]).await;
+    assert_custom_error(result, ErrorCode::AccountNotInitialized.into());
+}
+
+#[tokio::test]
+async fn deposit_nft_checks_the_collection() {
+    let (mut context, owner) = setup().await;
+    send(&mut context, &[initialize_ix(&owner.pubkey(), 0)], &[&owner]).await.unwrap();
+
+    let collection_mint = create_nft(&mut context, &owner, 1, 0, true).await;
+    let other_collection = create_nft(&mut context, &owner, 1, 0, true).await;
+    let mint = create_nft(&mut context, &owner, 1, 0, true).await;
+
+    let cases = [
+        (None, VaultError::MissingCollection),
+        (Some((collection_mint, false)), VaultError::UnverifiedCollection), // anyone can claim a collection, only the collection authority verifies it
+        (Some((other_collection, true)), VaultError::WrongCollection),
+    ];
+    for (collection, error) in cases {
+        set_metadata(&mut context, &mint, collection);
+        let deposit = nft_payment_ix(&owner.pubkey(), mint, Some(collection_mint), anchor_vault::instruction::DepositNft {}.data());
+        let result = send(&mut context, &[deposit], &[&owner]).await;
+        assert_custom_error(result, error.into());
+    }
+}
@@ -3,6 +3,7 @@ import { Program } from "@coral-xyz/anchor";
 import { AnchorVault } from "../target/types/anchor_vault";
 import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
 import { BN } from "bn.js";
+import { assert } from "chai";
 
 describe("anchor-vault", () => {
   // Configure the client to use the local cluster.
@@ -14,9 +15,30 @@ describe("anchor-vault", () => {
 
   const signer = Keypair.generate();
 
+  const vaultId = new BN(0);
+
+  const state = PublicKey.findProgramAddressSync([
+    Buffer.from("state"),
+    signer.publicKey.toBuffer(),
+    vaultId.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  )[0];
+
+  const creatorIndex = PublicKey.findProgramAddressSync([
+    Buffer.from("creator_index"),
+    signer.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
   const vault = PublicKey.findProgramAddressSync([
     Buffer.from("vault"),
-    signer.publicKey.toBuffer()],
+    state.toBuffer()],
+    program.programId
+  )[0];
+
+  const recovery = PublicKey.findProgramAddressSync([
+    Buffer.from("recovery"),
+    state.toBuffer()],
     program.programId
   )[0];
 
@@ -40,13 +62,30 @@ describe("anchor-vault", () => {
       .then(log);
   })
 
+  it("Initialize", async () => {
+    const tx = await program.methods
+      .initialize(vaultId, new BN(0))
+      .accounts({
+        owner: signer.publicKey,
+        state,
+        creatorIndex,
+        vault,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+  });
+
   it("Deposit", async () => {
     const tx = await program.methods
       .deposit(new BN(1e9))
       .accounts({
-        signer: signer.publicKey,
+        owner: signer.publicKey,
+        state,
         vault,
-        systemProgam: SystemProgram.programId
+        systemProgram: SystemProgram.programId
       })
       .signers([signer])
       .rpc()
@@ -56,11 +95,98 @@ describe("anchor-vault", () => {
 
   it("Withdraw", async () => {
     const tx = await program.methods
-      .withdraw(new BN(1e9))
+      .withdraw(new BN(5e8))
       .accounts({
-        signer: signer.publicKey,
+        owner: signer.publicKey,
+        state,
         vault,
-        systemProgam: SystemProgram.programId
+        systemProgram: SystemProgram.programId
+      })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+  });
+
+  it("Withdraw more than the vault holds", async () => {
+    try {
+      await program.methods
+        .withdraw(new BN(10e9))
+        .accounts({
+          owner: signer.publicKey,
+          state,
+          vault,
+          systemProgram: SystemProgram.programId
+        })
+        .signers([signer])
+        .rpc();
+      assert.fail("withdraw should have failed");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InsufficientFunds");
+    }
+  });
+
+  it("Close", async () => {
+    const tx = await program.methods
+      .close()
+      .accounts({
+        owner: signer.publicKey,
+        state,
+        creatorIndex,
+        vault,
+        recovery,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+  });
+
+  // the provider wallet funds a vesting schedule for the signer
+  const funder = anchor.getProvider().publicKey;
+  const seed = new BN(1);
+
+  const vesting = PublicKey.findProgramAddressSync([
+    Buffer.from("vesting"),
+    funder.toBuffer(),
+    signer.publicKey.toBuffer(),
+    seed.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  )[0];
+
+  const vestingVault = PublicKey.findProgramAddressSync([
+    Buffer.from("vault"),
+    vesting.toBuffer()],
+    program.programId
+  )[0];
+
+  it("Create vesting", async () => {
+    // already fully vested so we can claim everything straight away
+    const now = Math.floor(Date.now() / 1000);
+    const tx = await program.methods
+      .createVesting(seed, new BN(1e9), new BN(now - 100), new BN(now - 50), new BN(now - 10))
+      .accounts({
+        funder,
+        beneficiary: signer.publicKey,
+        vesting,
+        vault: vestingVault,
+        systemProgram: SystemProgram.programId
+      })
+      .rpc()
+      .then(confirm)
+      .then(log);
+  });
+
+  it("Claim", async () => {
+    const tx = await program.methods
+      .claim()
+      .accounts({
+        beneficiary: signer.publicKey,
+        funder,
+        vesting,
+        vault: vestingVault,
+        systemProgram: SystemProgram.programId
       })
       .signers([signer])
       .rpc()